@@ -13,23 +13,23 @@ fn best_list(c: &mut Criterion) {
         .collect();
 
     fn bests_1(list: &[i32], n: usize) -> Vec<i32> {
-        let mut sorted: Vec<_> = list.iter().cloned().collect();
+        let mut sorted: Vec<_> = list.to_vec();
         sorted.sort();
         sorted.iter().take(n).cloned().collect()
     }
 
     fn bests_2(list: &[i32], n: usize) -> Vec<i32> {
-        let best_list = BestList::from_vec(&list, n);
+        let best_list = BestList::from_vec(list, n);
 
         best_list.into()
     }
 
     for v in lists.iter() {
         group.bench_with_input(BenchmarkId::new("sort + take", v.len()), v, |b, v| {
-            b.iter(|| bests_1(&v, 10))
+            b.iter(|| bests_1(v, 10))
         });
         group.bench_with_input(BenchmarkId::new("BestList", v.len()), v, |b, v| {
-            b.iter(|| bests_2(&v, 10))
+            b.iter(|| bests_2(v, 10))
         });
     }
     group.finish();
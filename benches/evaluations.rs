@@ -1,24 +1,30 @@
 #[allow(unused)]
 use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
 use rust_hex::{
-    board::Board,
-    evaluation::{Evaluation, Evaluation1, Evaluation2, Evaluation3, Evaluation4},
+    board::{Board, DEFAULT_BOARD_SIZE},
+    evaluation::{
+        Evaluation, Evaluation1, Evaluation2, Evaluation3, Evaluation4, Evaluation5, Evaluation6,
+        Evaluation7,
+    },
 };
 
 fn evaluation(c: &mut Criterion) {
     let mut group = c.benchmark_group("Evaluation");
     let boards = [
-        Board::new(),
-        Board::random_board(10),
-        Board::random_board(20),
-        Board::random_board(30),
-        Board::random_board(40),
+        Board::default(),
+        Board::random_board(DEFAULT_BOARD_SIZE, 10),
+        Board::random_board(DEFAULT_BOARD_SIZE, 20),
+        Board::random_board(DEFAULT_BOARD_SIZE, 30),
+        Board::random_board(DEFAULT_BOARD_SIZE, 40),
     ];
 
     let evaluation1 = Evaluation1::new();
     let evaluation2 = Evaluation2::new();
     let evaluation3 = Evaluation3::new();
     let evaluation4 = Evaluation4::new();
+    let evaluation5 = Evaluation5::new();
+    let evaluation6 = Evaluation6::new();
+    let evaluation7 = Evaluation7::new();
 
     for (i, v) in boards.iter().enumerate() {
         group.bench_with_input(BenchmarkId::new("Evaluation1", i), v, |b, v| {
@@ -33,6 +39,15 @@ fn evaluation(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("Evaluation4", i), v, |b, v| {
             b.iter(|| Evaluation::score(&evaluation4, v))
         });
+        group.bench_with_input(BenchmarkId::new("Evaluation5", i), v, |b, v| {
+            b.iter(|| Evaluation::score(&evaluation5, v))
+        });
+        group.bench_with_input(BenchmarkId::new("Evaluation6", i), v, |b, v| {
+            b.iter(|| Evaluation::score(&evaluation6, v))
+        });
+        group.bench_with_input(BenchmarkId::new("Evaluation7", i), v, |b, v| {
+            b.iter(|| Evaluation::score(&evaluation7, v))
+        });
     }
     group.finish();
 }
@@ -0,0 +1,48 @@
+use std::rc::Rc;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use rust_hex::{
+    board::Board,
+    evaluation::{Evaluation, Evaluation2, Evaluation4},
+    strategy::{AlphaBeta4, Strategy},
+};
+
+/// Nodes per second of a fixed-depth AlphaBeta4 search, which plays and
+/// undoes moves on a single board and scores the leaves, with and without
+/// inferior cell pruning at the interior nodes.
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Search");
+    group.sample_size(10);
+
+    let evaluations: [(&str, Rc<dyn Evaluation>); 2] = [
+        ("Evaluation2", Rc::new(Evaluation2::new())),
+        ("Evaluation4", Rc::new(Evaluation4::new())),
+    ];
+    for (size, depth) in [(7, 3), (11, 2)] {
+        let mut board = Board::new(size);
+        for (x, y) in [(size / 2, size / 2), (1, size - 2), (size - 2, 1)] {
+            board.play(x, y);
+        }
+
+        for (name, evaluation) in &evaluations {
+            for inferior_pruning in [false, true] {
+                let mut player = AlphaBeta4::new(evaluation.clone(), depth, Rc::default());
+                player.set_inferior_pruning(inferior_pruning);
+                player.next_move(&board, None);
+                group.throughput(Throughput::Elements(player.nodes()));
+                let name = if inferior_pruning {
+                    format!("{name} pruned")
+                } else {
+                    name.to_string()
+                };
+                group.bench_with_input(BenchmarkId::new(name, size), &board, |b, board| {
+                    b.iter(|| player.next_move(board, None))
+                });
+            }
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, search);
+criterion_main!(benches);
@@ -79,6 +79,7 @@ where
 }
 
 #[cfg(test)]
+#[allow(clippy::get_first)]
 mod tests {
     use std::vec;
 
@@ -0,0 +1,306 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign};
+
+use crate::cell::NEIGHBOR_STEPS;
+
+/// Largest board side supported by the bit layout.
+pub const MAX_BOARD_SIZE: usize = 19;
+
+/// Cells are stored row by row with one padding bit at the end of each row,
+/// so that shifting a set never wraps a stone onto the next row.
+const MAX_STRIDE: usize = MAX_BOARD_SIZE + 1;
+/// Number of bit positions, padding included, for tables indexed like a
+/// bitboard.
+pub const NB_INDICES: usize = MAX_BOARD_SIZE * MAX_STRIDE;
+const WORDS: usize = NB_INDICES.div_ceil(64);
+
+/// Marks the end of a cell's neighbors in `NEIGHBORS`.
+const NO_NEIGHBOR: u16 = u16::MAX;
+
+/// Indices of the neighbors of every cell, for every board size, so that
+/// loops over neighbors neither allocate nor recompute coordinates.
+static NEIGHBORS: [[[u16; 6]; NB_INDICES]; MAX_BOARD_SIZE + 1] = neighbor_table();
+
+const fn neighbor_table() -> [[[u16; 6]; NB_INDICES]; MAX_BOARD_SIZE + 1] {
+    let mut table = [[[NO_NEIGHBOR; 6]; NB_INDICES]; MAX_BOARD_SIZE + 1];
+    let mut size = 1;
+    while size <= MAX_BOARD_SIZE {
+        let mut x = 0;
+        while x < size {
+            let mut y = 0;
+            while y < size {
+                let mut count = 0;
+                let mut step = 0;
+                while step < NEIGHBOR_STEPS.len() {
+                    let (nx, ny) = (
+                        x as i32 + NEIGHBOR_STEPS[step].0,
+                        y as i32 + NEIGHBOR_STEPS[step].1,
+                    );
+                    if nx >= 0 && ny >= 0 && nx < size as i32 && ny < size as i32 {
+                        table[size][Bitboard::index(size, x, y)][count] =
+                            Bitboard::index(size, nx as usize, ny as usize) as u16;
+                        count += 1;
+                    }
+                    step += 1;
+                }
+                y += 1;
+            }
+            x += 1;
+        }
+        size += 1;
+    }
+    table
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard([u64; WORDS]);
+
+impl Bitboard {
+    pub const fn empty() -> Bitboard {
+        Bitboard([0; WORDS])
+    }
+
+    pub const fn stride(size: usize) -> usize {
+        size + 1
+    }
+
+    pub const fn index(size: usize, x: usize, y: usize) -> usize {
+        x * Bitboard::stride(size) + y
+    }
+
+    pub const fn coord(size: usize, index: usize) -> (usize, usize) {
+        (
+            index / Bitboard::stride(size),
+            index % Bitboard::stride(size),
+        )
+    }
+
+    /// Indices of the cells next to the one at `index`, in the order of
+    /// `Cell::neighbors`.
+    pub fn neighbor_indices(size: usize, index: usize) -> impl Iterator<Item = usize> {
+        NEIGHBORS[size][index]
+            .iter()
+            .take_while(|&&neighbor| neighbor != NO_NEIGHBOR)
+            .map(|&neighbor| neighbor as usize)
+    }
+
+    /// Every cell of a `size` x `size` board.
+    pub fn full(size: usize) -> Bitboard {
+        let mut bitboard = Bitboard::empty();
+        for x in 0..size {
+            bitboard |= Bitboard::row(size, x);
+        }
+        bitboard
+    }
+
+    pub fn row(size: usize, x: usize) -> Bitboard {
+        let mut bitboard = Bitboard::empty();
+        for y in 0..size {
+            bitboard.set(Bitboard::index(size, x, y));
+        }
+        bitboard
+    }
+
+    pub fn column(size: usize, y: usize) -> Bitboard {
+        let mut bitboard = Bitboard::empty();
+        for x in 0..size {
+            bitboard.set(Bitboard::index(size, x, y));
+        }
+        bitboard
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn set(&mut self, index: usize) {
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn clear(&mut self, index: usize) {
+        self.0[index / 64] &= !(1 << (index % 64));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Cells in `self` but not in `other`.
+    pub fn and_not(&self, other: Bitboard) -> Bitboard {
+        let mut result = *self;
+        for (word, other) in result.0.iter_mut().zip(other.0) {
+            *word &= !other;
+        }
+        result
+    }
+
+    fn shift_up(&self, n: usize) -> Bitboard {
+        let (words, bits) = (n / 64, n % 64);
+        let mut result = Bitboard::empty();
+        for i in (words..WORDS).rev() {
+            let mut word = self.0[i - words] << bits;
+            if bits > 0 && i > words {
+                word |= self.0[i - words - 1] >> (64 - bits);
+            }
+            result.0[i] = word;
+        }
+        result
+    }
+
+    fn shift_down(&self, n: usize) -> Bitboard {
+        let (words, bits) = (n / 64, n % 64);
+        let mut result = Bitboard::empty();
+        for i in 0..WORDS - words {
+            let mut word = self.0[i + words] >> bits;
+            if bits > 0 && i + words + 1 < WORDS {
+                word |= self.0[i + words + 1] << (64 - bits);
+            }
+            result.0[i] = word;
+        }
+        result
+    }
+
+    /// Cells adjacent to at least one cell of `self`, following the same six
+    /// directions as `Cell::neighbors`.
+    pub fn neighbors(&self, size: usize) -> Bitboard {
+        let stride = Bitboard::stride(size);
+        (self.shift_up(1)
+            | self.shift_down(1)
+            | self.shift_up(stride)
+            | self.shift_down(stride)
+            | self.shift_up(stride - 1)
+            | self.shift_down(stride - 1))
+            & Bitboard::full(size)
+    }
+
+    /// Grows `self` inside `region` until it covers every cell of `region`
+    /// connected to it.
+    pub fn flood_fill(&self, region: Bitboard, size: usize) -> Bitboard {
+        let mut filled = *self & region;
+        loop {
+            let next = (filled | filled.neighbors(size)) & region;
+            if next == filled {
+                return filled;
+            }
+            filled = next;
+        }
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = usize> {
+        self.0.into_iter().enumerate().flat_map(|(i, mut word)| {
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(mut self, other: Bitboard) -> Bitboard {
+        self &= other;
+        self
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, other: Bitboard) {
+        for (word, other) in self.0.iter_mut().zip(other.0) {
+            *word &= other;
+        }
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(mut self, other: Bitboard) -> Bitboard {
+        self |= other;
+        self
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, other: Bitboard) {
+        for (word, other) in self.0.iter_mut().zip(other.0) {
+            *word |= other;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+
+    #[test]
+    fn set_get_clear() {
+        let mut bitboard = Bitboard::empty();
+        let index = Bitboard::index(MAX_BOARD_SIZE, 18, 18);
+        bitboard.set(index);
+        assert!(bitboard.get(index));
+        assert_eq!(bitboard.count(), 1);
+        bitboard.clear(index);
+        assert!(bitboard.is_empty());
+    }
+
+    #[test]
+    fn neighbors_match_cell() {
+        for size in [2, 7, 11, MAX_BOARD_SIZE] {
+            for x in 0..size {
+                for y in 0..size {
+                    let mut bitboard = Bitboard::empty();
+                    bitboard.set(Bitboard::index(size, x, y));
+
+                    let mut expected = Bitboard::empty();
+                    for cell in Cell::new(x as i32, y as i32).neighbors(size) {
+                        expected.set(Bitboard::index(size, cell.x as usize, cell.y as usize));
+                    }
+
+                    assert_eq!(bitboard.neighbors(size), expected);
+
+                    let indices: Vec<usize> =
+                        Bitboard::neighbor_indices(size, Bitboard::index(size, x, y)).collect();
+                    let cells: Vec<usize> = Cell::new(x as i32, y as i32)
+                        .neighbors(size)
+                        .map(|cell| Bitboard::index(size, cell.x as usize, cell.y as usize))
+                        .collect();
+                    assert_eq!(indices, cells);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn flood_fill() {
+        let size = 4;
+        let mut region = Bitboard::empty();
+        for (x, y) in [(0, 0), (1, 0), (2, 0), (3, 3)] {
+            region.set(Bitboard::index(size, x, y));
+        }
+        let seed = Bitboard::row(size, 0);
+
+        let filled = seed.flood_fill(region, size);
+        assert_eq!(filled.count(), 3);
+        assert!(!filled.get(Bitboard::index(size, 3, 3)));
+    }
+
+    #[test]
+    fn iter() {
+        let size = 3;
+        let cells: Vec<_> = Bitboard::column(size, 1)
+            .iter()
+            .map(|index| Bitboard::coord(size, index))
+            .collect();
+        assert_eq!(cells, vec![(0, 1), (1, 1), (2, 1)]);
+    }
+}
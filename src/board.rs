@@ -1,109 +1,556 @@
 use core::fmt;
 use std::collections::VecDeque;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 
 use rand::Rng;
 
+pub use crate::bitboard::MAX_BOARD_SIZE;
+use crate::bitboard::{Bitboard, NB_INDICES};
 use crate::cell::Cell;
+use crate::circuit::Circuit;
 use crate::color::Color;
 use crate::distance::Distance;
+use crate::distance_map::DistanceMap;
+use crate::error::HexError;
+use crate::flow::{FlowNetwork, UNBOUNDED};
+use crate::group::{Edge, Group};
+use crate::hex_move::{self, Move};
+use crate::symmetry::Symmetry;
+use crate::union_find::{BOTTOM, LEFT, RIGHT, TOP, UnionFind};
+use crate::vc::VirtualConnections;
+use crate::zobrist;
 
 //pub type Board = Board<11>;
 
 pub const DEFAULT_BOARD_SIZE: usize = 7;
+pub const MIN_BOARD_SIZE: usize = 2;
 
-#[allow(unused)]
-#[derive(Debug, Clone, PartialEq, Hash, Eq)]
-pub struct Board<const SIZE: usize = DEFAULT_BOARD_SIZE> {
-    board: [[Color; SIZE]; SIZE],
+/// Nodes of a player's edges in their connection graph.
+const START: usize = 0;
+const END: usize = 1;
+
+#[derive(Debug, Clone)]
+pub struct Board {
+    size: usize,
+    white: Bitboard,
+    black: Bitboard,
     next_color: Color,
+    /// Stone groups joined to the virtual edge nodes, updated on every move.
+    /// The winner is read from them.
+    groups: UnionFind,
+    /// Distances from each player's first edge, updated on every move, for
+    /// the evaluations.
+    black_distances: DistanceMap,
+    white_distances: DistanceMap,
     winner: Color,
+    /// Zobrist keys of the position seen through each symmetry, in the
+    /// order of `Symmetry::ALL`, the first one being the position itself.
+    keys: [u64; 4],
+    history: Vec<Move>,
+    swap_rule: bool,
 }
 
-impl<const SIZE: usize> Board<SIZE> {
-    pub fn new() -> Board<SIZE> {
-        Board {
-            board: [[Color::None; SIZE]; SIZE],
+impl Board {
+    /// An empty board. Panics if the size is not between `MIN_BOARD_SIZE`
+    /// and `MAX_BOARD_SIZE`, see `try_new`.
+    pub fn new(size: usize) -> Board {
+        Board::try_new(size).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_new(size: usize) -> Result<Board, HexError> {
+        if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) {
+            return Err(HexError::InvalidSize { size });
+        }
+
+        Ok(Board {
+            size,
+            white: Bitboard::empty(),
+            black: Bitboard::empty(),
             next_color: Color::White,
+            groups: UnionFind::new(),
+            black_distances: DistanceMap::new(size, Color::Black),
+            white_distances: DistanceMap::new(size, Color::White),
             winner: Color::None,
-        }
+            keys: Symmetry::ALL.map(|symmetry| {
+                zobrist::size_key(size) ^ zobrist::side_key(symmetry.color(Color::White))
+            }),
+            history: Vec::with_capacity(size * size + 1),
+            swap_rule: false,
+        })
     }
 
     pub fn size(&self) -> usize {
-        SIZE
+        self.size
     }
 
     pub fn play(&mut self, x: usize, y: usize) {
-        self.board[x][y] = self.next_color;
-        self.next_color = self.next_color.opponent();
+        let color = self.next_color;
+        self.place(color, x, y);
+        self.set_next_color(color.opponent());
+        self.history.push(Move::Cell(x, y));
+    }
+
+    /// Puts a stone on an empty cell without playing a move: the side to
+    /// move and the history are left alone. Used to fill in cells whose
+    /// owner is already known.
+    pub fn place_stone(&mut self, color: Color, x: usize, y: usize) {
+        debug_assert!(self.is_valid(x, y));
+        self.place(color, x, y);
+    }
+
+    /// Takes back the last move played, restoring the side to move, the key
+    /// and the winner. Returns `None` when there is no move to take back.
+    pub fn undo(&mut self) -> Option<Move> {
+        let last_move = self.history.pop()?;
+        match last_move {
+            Move::Cell(x, y) => self.remove(self.next_color.opponent(), x, y),
+            Move::Swap => {
+                let (x, y) = self.first_stone();
+                self.remove(Color::Black, y, x);
+                self.place(Color::White, x, y);
+            }
+            Move::Resign => unreachable!("resignations are not part of the board history"),
+        }
+        self.set_next_color(self.next_color.opponent());
+
+        Some(last_move)
+    }
+
+    /// Moves played since the board was created, oldest first.
+    pub fn moves(&self) -> &[Move] {
+        &self.history
+    }
+
+    pub fn swap_rule(&self) -> bool {
+        self.swap_rule
+    }
+
+    pub fn set_swap_rule(&mut self, swap_rule: bool) {
+        self.swap_rule = swap_rule;
+    }
+
+    /// Whether the side to move may swap: the swap rule is on and exactly
+    /// one stone has been played.
+    pub fn can_swap(&self) -> bool {
+        self.swap_rule && matches!(self.history.as_slice(), [Move::Cell(_, _)])
+    }
+
+    /// Applies the swap rule: the first stone is mirrored across the long
+    /// diagonal and changes color, so the players exchange roles and the
+    /// first player moves again.
+    pub fn swap(&mut self) -> Result<(), HexError> {
+        if !self.can_swap() {
+            return Err(HexError::SwapNotAllowed);
+        }
+
+        let (x, y) = self.first_stone();
+        self.remove(Color::White, x, y);
+        self.place(Color::Black, y, x);
+        self.set_next_color(Color::White);
+        self.history.push(Move::Swap);
+        Ok(())
+    }
+
+    fn first_stone(&self) -> (usize, usize) {
+        match self.history.first() {
+            Some(Move::Cell(x, y)) => (*x, *y),
+            _ => unreachable!("the swap rule applies to the first stone"),
+        }
+    }
+
+    fn place(&mut self, color: Color, x: usize, y: usize) {
+        let index = Bitboard::index(self.size, x, y);
+        match color {
+            Color::White => self.white.set(index),
+            Color::Black => self.black.set(index),
+            Color::None => unreachable!(),
+        }
+        self.toggle_stone_keys(color, x, y);
+        self.update_distances(index, Color::None);
+        self.join_groups(color, x, y);
+    }
+
+    fn remove(&mut self, color: Color, x: usize, y: usize) {
+        let index = Bitboard::index(self.size, x, y);
+        match color {
+            Color::White => self.white.clear(index),
+            Color::Black => self.black.clear(index),
+            Color::None => unreachable!(),
+        }
+        self.toggle_stone_keys(color, x, y);
+        self.update_distances(index, color);
+        self.rebuild_groups();
+    }
+
+    /// Brings both distance maps up to date after the cell at `index`, which
+    /// held `before`, changed.
+    fn update_distances(&mut self, index: usize, before: Color) {
+        self.black_distances
+            .update(self.black, self.white, index, before);
+        self.white_distances
+            .update(self.white, self.black, index, before);
+    }
+
+    fn join_groups(&mut self, color: Color, x: usize, y: usize) {
+        let index = Bitboard::index(self.size, x, y);
+        let stones = self.stones(color);
+
+        for neighbor in Bitboard::neighbor_indices(self.size, index) {
+            if stones.get(neighbor) {
+                self.groups.union(index, neighbor);
+            }
+        }
+
+        let (start, end) = match color {
+            Color::Black => {
+                if x == 0 {
+                    self.groups.union(index, TOP);
+                }
+                if x == self.size - 1 {
+                    self.groups.union(index, BOTTOM);
+                }
+                (TOP, BOTTOM)
+            }
+            Color::White => {
+                if y == 0 {
+                    self.groups.union(index, LEFT);
+                }
+                if y == self.size - 1 {
+                    self.groups.union(index, RIGHT);
+                }
+                (LEFT, RIGHT)
+            }
+            Color::None => unreachable!(),
+        };
+
+        if self.winner == Color::None && self.groups.is_connected(start, end) {
+            self.winner = color;
+        }
+    }
+
+    /// Union-find cannot split sets, so taking a stone away rebuilds the
+    /// groups from the remaining stones.
+    fn rebuild_groups(&mut self) {
+        self.groups = UnionFind::new();
+        self.winner = Color::None;
+        for color in [Color::Black, Color::White] {
+            for index in self.stones(color).iter() {
+                let (x, y) = Bitboard::coord(self.size, index);
+                self.join_groups(color, x, y);
+            }
+        }
+    }
+
+    fn distance_map(&self, color: Color) -> &DistanceMap {
+        match color {
+            Color::Black => &self.black_distances,
+            Color::White => &self.white_distances,
+            Color::None => panic!("Color::None has no distances"),
+        }
+    }
+
+    pub(crate) fn set_next_color(&mut self, color: Color) {
+        for (key, symmetry) in self.keys.iter_mut().zip(Symmetry::ALL) {
+            *key ^= zobrist::side_key(symmetry.color(self.next_color))
+                ^ zobrist::side_key(symmetry.color(color));
+        }
+        self.next_color = color;
+    }
+
+    fn toggle_stone_keys(&mut self, color: Color, x: usize, y: usize) {
+        for (key, symmetry) in self.keys.iter_mut().zip(Symmetry::ALL) {
+            let (x, y) = symmetry.cell(self.size, x, y);
+            *key ^= zobrist::stone_key(symmetry.color(color), x, y);
+        }
+    }
+
+    /// Zobrist key of the position, including the side to move. Keys are
+    /// stable across runs, so they can be stored alongside positions.
+    pub fn key(&self) -> u64 {
+        self.keys[0]
+    }
+
+    /// The equivalent position seen through `symmetry`. Rotating keeps the
+    /// move history, rotated as well; after a color swap the position can no
+    /// longer be reached by White moving first, so the history is dropped.
+    pub fn transform(&self, symmetry: Symmetry) -> Board {
+        let mut board = Board::new(self.size);
+        board.swap_rule = self.swap_rule;
+        for color in [Color::White, Color::Black] {
+            for index in self.stones(color).iter() {
+                let (x, y) = Bitboard::coord(self.size, index);
+                let (x, y) = symmetry.cell(self.size, x, y);
+                board.place(symmetry.color(color), x, y);
+            }
+        }
+        board.set_next_color(symmetry.color(self.next_color));
+
+        if !symmetry.swaps_colors() {
+            board.history = self
+                .history
+                .iter()
+                .map(|mv| mv.transform(symmetry, self.size))
+                .collect();
+        }
+        board
+    }
+
+    #[allow(unused)]
+    pub fn rotate180(&self) -> Board {
+        self.transform(Symmetry::Rotate180)
+    }
+
+    #[allow(unused)]
+    pub fn transpose_swap(&self) -> Board {
+        self.transform(Symmetry::TransposeSwap)
+    }
+
+    /// Key of `self.transform(symmetry)`, kept up to date along with `key`.
+    pub fn transformed_key(&self, symmetry: Symmetry) -> u64 {
+        self.keys[symmetry as usize]
+    }
+
+    /// The smallest key among the equivalent positions, and the symmetry
+    /// leading to it. Equivalent positions share the same canonical key.
+    pub fn canonical_key(&self) -> (u64, Symmetry) {
+        self.keys
+            .into_iter()
+            .zip(Symmetry::ALL)
+            .min_by_key(|(key, _)| *key)
+            .unwrap()
+    }
+
+    /// The representative of the position's symmetry class, with the
+    /// symmetry mapping `self` to it (and back, as symmetries are
+    /// involutions).
+    #[allow(unused)]
+    pub fn canonical(&self) -> (Board, Symmetry) {
+        let (_, symmetry) = self.canonical_key();
+        (self.transform(symmetry), symmetry)
+    }
+
+    /// Plays for the side to move after checking that the move is legal.
+    pub fn try_play(&mut self, x: usize, y: usize) -> Result<(), HexError> {
+        self.try_play_as(self.next_color, x, y)
+    }
+
+    /// Like `try_play`, but also checks that `color` is the side to move.
+    pub fn try_play_as(&mut self, color: Color, x: usize, y: usize) -> Result<(), HexError> {
+        if self.winner != Color::None {
+            return Err(HexError::GameFinished);
+        }
+        if color != self.next_color {
+            return Err(HexError::WrongColor {
+                expected: self.next_color,
+                found: color,
+            });
+        }
+        if x >= self.size || y >= self.size {
+            return Err(HexError::OutOfBounds { x, y });
+        }
+        if !self.is_valid(x, y) {
+            return Err(HexError::Occupied { x, y });
+        }
+
+        self.play(x, y);
+        Ok(())
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        let index = Bitboard::index(self.size, x, y);
+        if self.white.get(index) {
+            Color::White
+        } else if self.black.get(index) {
+            Color::Black
+        } else {
+            Color::None
+        }
+    }
+
+    pub fn get_board(&self) -> Vec<Vec<Color>> {
+        let mut board = vec![vec![Color::None; self.size]; self.size];
+        for (x, row) in board.iter_mut().enumerate() {
+            for (y, cell) in row.iter_mut().enumerate() {
+                *cell = self.get(x, y);
+            }
+        }
+        board
     }
 
-    pub fn get_board(&self) -> &[[Color; SIZE]; SIZE] {
-        &self.board
+    pub fn stones(&self, color: Color) -> Bitboard {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+            Color::None => Bitboard::full(self.size).and_not(self.white | self.black),
+        }
+    }
+
+    /// Connected components of `color`'s stones, with their liberties and
+    /// the edges they touch, ordered by their first stone.
+    pub fn groups(&self, color: Color) -> Vec<Group> {
+        let mut visited = vec![vec![false; self.size]; self.size];
+        let mut groups = Vec::new();
+
+        for (x, y) in (0..self.size).flat_map(|x| (0..self.size).map(move |y| (x, y))) {
+            if visited[x][y] || self.get(x, y) != color {
+                continue;
+            }
+
+            let mut cells = Vec::new();
+            let mut liberties = Vec::new();
+            let mut edges = Vec::new();
+            let mut stack = vec![(x, y)];
+            visited[x][y] = true;
+            while let Some((x, y)) = stack.pop() {
+                cells.push((x, y));
+                edges.extend(Edge::around(self.size, x, y));
+                for neighbor in Cell::new(x as i32, y as i32).neighbors(self.size) {
+                    let (nx, ny) = (neighbor.x as usize, neighbor.y as usize);
+                    match self.get(nx, ny) {
+                        Color::None if !liberties.contains(&(nx, ny)) => liberties.push((nx, ny)),
+                        stone if stone == color && !visited[nx][ny] => {
+                            visited[nx][ny] = true;
+                            stack.push((nx, ny));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            cells.sort();
+            liberties.sort();
+            edges.sort_by_key(|&edge| edge as usize);
+            edges.dedup();
+            groups.push(Group {
+                color,
+                cells,
+                liberties,
+                edges,
+            });
+        }
+
+        groups
     }
 
     pub fn is_valid(&self, x: usize, y: usize) -> bool {
-        if x >= SIZE || y >= SIZE {
+        if x >= self.size || y >= self.size {
             return false;
         }
 
-        self.board[x][y] == Color::None
+        !(self.white | self.black).get(Bitboard::index(self.size, x, y))
+    }
+
+    /// Whether the stones of `color` link its two edges: top and bottom rows
+    /// for Black, left and right columns for White.
+    pub fn is_connected(&self, color: Color) -> bool {
+        let (start, end) = match color {
+            Color::Black => (
+                Bitboard::row(self.size, 0),
+                Bitboard::row(self.size, self.size - 1),
+            ),
+            Color::White => (
+                Bitboard::column(self.size, 0),
+                Bitboard::column(self.size, self.size - 1),
+            ),
+            Color::None => panic!("Player::None has no edges"),
+        };
+        let stones = self.stones(color);
+
+        !(start.flood_fill(stones, self.size) & end).is_empty()
     }
 
     pub fn next_color(&self) -> Color {
         self.next_color
     }
 
-    fn reach(
-        &self,
-        player: Color,
-        distance: usize,
-        distances: Vec<Vec<Distance>>,
-    ) -> (Vec<Vec<Distance>>, bool) {
-        //println!("reach called with dist of {}", dist);
-        let mut new_distances = distances.clone();
-        let mut changed = false;
+    /// Number of moves `color` is missing to connect its edges, `None` when
+    /// they are cut off. Read from the distances kept up to date by `play`.
+    pub fn missing_move_to_win(&self, color: Color) -> Option<usize> {
+        self.distance_map(color).to_end()
+    }
 
-        for i in 0..SIZE {
-            for j in 0..SIZE {
-                if let Distance::Reachable(d) = distances[i][j] {
-                    if d == distance {
-                        for neighbor in Cell::new(i as i32, j as i32).neighbors(SIZE) {
-                            if let Distance::Unexplored =
-                                distances[neighbor.x as usize][neighbor.y as usize]
-                            {
-                                changed = true;
-                                match self.board[neighbor.x as usize][neighbor.y as usize] {
-                                    Color::None => {
-                                        new_distances[neighbor.x as usize][neighbor.y as usize] =
-                                            Distance::Reachable(distance + 1)
-                                    }
-                                    p => {
-                                        if p == player {
-                                            new_distances[neighbor.x as usize]
-                                                [neighbor.y as usize] =
-                                                Distance::Reachable(distance);
-                                            (new_distances, _) =
-                                                self.reach(player, distance, new_distances);
-                                        } else {
-                                            new_distances[neighbor.x as usize]
-                                                [neighbor.y as usize] = Distance::Unreachable;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+    /// Distance of `(x, y)` from `color`'s first edge (top for Black, left
+    /// for White): the number of empty cells on the best path to it, itself
+    /// included. The opponent's stones are `Distance::Unreachable`, and so
+    /// are cells they wall off.
+    pub fn distance(&self, color: Color, x: usize, y: usize) -> Distance {
+        match self.distance_map(color).get(x, y) {
+            Some(distance) => Distance::Reachable(distance),
+            None => Distance::Unreachable,
+        }
+    }
+
+    pub fn get_dist_matrix(&self, color: Color) -> Vec<Vec<Distance>> {
+        (0..self.size)
+            .map(|x| (0..self.size).map(|y| self.distance(color, x, y)).collect())
+            .collect()
+    }
+
+    /// Same as `missing_move_to_win`, recomputed from the stones with a 0-1
+    /// breadth-first search over the grid instead of read from the
+    /// distances kept by `play`. Slower, it serves to cross-check them.
+    pub fn missing_move_to_win2(&self, color: Color) -> Option<usize> {
+        // Cell `i` along the row or column `line` crossed by `color`.
+        let edge_cell = |line: usize, i: usize| match color {
+            Color::Black => (line, i),
+            Color::White => (i, line),
+            Color::None => panic!("Color::None has no missing move to win"),
+        };
+        let cost = |x: usize, y: usize| match self.get(x, y) {
+            Color::None => Some(1),
+            stone if stone == color => Some(0),
+            _ => None,
+        };
+
+        let mut distances = vec![None; self.size * self.size];
+        let mut queue = VecDeque::new();
+        for i in 0..self.size {
+            let (x, y) = edge_cell(0, i);
+            match cost(x, y) {
+                Some(0) => queue.push_front((x, y, 0)),
+                Some(distance) => queue.push_back((x, y, distance)),
+                None => continue,
+            }
+            distances[x * self.size + y] = cost(x, y);
+        }
+
+        while let Some((x, y, distance)) = queue.pop_front() {
+            if distances[x * self.size + y] < Some(distance) {
+                continue;
+            }
+            for neighbor in Cell::new(x as i32, y as i32).neighbors(self.size) {
+                let (nx, ny) = (neighbor.x as usize, neighbor.y as usize);
+                let Some(step) = cost(nx, ny) else {
+                    continue;
+                };
+                let known = distances[nx * self.size + ny];
+                if known.is_some_and(|known| known <= distance + step) {
+                    continue;
+                }
+                distances[nx * self.size + ny] = Some(distance + step);
+                match step {
+                    0 => queue.push_front((nx, ny, distance + step)),
+                    _ => queue.push_back((nx, ny, distance + step)),
                 }
             }
         }
 
-        (new_distances, changed)
+        (0..self.size)
+            .filter_map(|i| {
+                let (x, y) = edge_cell(self.size - 1, i);
+                distances[x * self.size + y]
+            })
+            .min()
     }
 
-    pub fn missing_move_to_win(&self, color: Color) -> Option<usize> {
+    /// The original `missing_move_to_win`: distances grow one layer at a
+    /// time from `color`'s first edge, each layer spreading through the
+    /// player's stones with `reach`. Kept as a reference for the distances
+    /// maintained by `play`.
+    #[cfg(test)]
+    pub(crate) fn missing_move_to_win_reach(&self, color: Color) -> Option<usize> {
         let is_finished = |distances: &Vec<Vec<Distance>>| {
-            for i in 0..SIZE {
+            for i in 0..self.size {
                 for row in distances.iter() {
                     if let Distance::Unexplored = row[i] {
                         return false;
@@ -116,8 +563,8 @@ impl<const SIZE: usize> Board<SIZE> {
         match color {
             Color::Black => {
                 let mut distances: Vec<Vec<Distance>> =
-                    vec![vec![Distance::Unexplored; SIZE]; SIZE];
-                distances[0] = self.board[0]
+                    vec![vec![Distance::Unexplored; self.size]; self.size];
+                distances[0] = self.get_board()[0]
                     .iter()
                     .map(|x| match x {
                         Color::None => Distance::Reachable(1),
@@ -134,7 +581,6 @@ impl<const SIZE: usize> Board<SIZE> {
                 // reach all cells
                 let mut dist = 0;
                 while !is_finished(&distances) {
-                    //println!("{} {:?}", dist, distances);
                     let changed;
                     (distances, changed) = self.reach(color, dist, distances);
                     dist += 1;
@@ -143,10 +589,8 @@ impl<const SIZE: usize> Board<SIZE> {
                     }
                 }
 
-                //println!("{:?}", distances);
-
                 // get the minimum distance to the last row
-                distances[SIZE - 1]
+                distances[self.size - 1]
                     .iter()
                     .filter_map(|x| match x {
                         Distance::Reachable(d) => Some(d),
@@ -157,14 +601,14 @@ impl<const SIZE: usize> Board<SIZE> {
             }
             Color::White => {
                 let mut distances: Vec<Vec<Distance>> =
-                    vec![vec![Distance::Unexplored; SIZE]; SIZE];
+                    vec![vec![Distance::Unexplored; self.size]; self.size];
 
                 //change first columns of distances to 1
                 for (y, row) in distances.iter_mut().enumerate() {
-                    row[0] = match self.board[y][0] {
+                    row[0] = match self.get(y, 0) {
                         Color::None => Distance::Reachable(1),
                         _ => {
-                            if self.board[y][0] == color {
+                            if self.get(y, 0) == color {
                                 Distance::Reachable(0)
                             } else {
                                 Distance::Unreachable
@@ -176,7 +620,6 @@ impl<const SIZE: usize> Board<SIZE> {
                 // reach all cells
                 let mut dist = 0;
                 while !is_finished(&distances) {
-                    //println!("{} {:?}", dist, distances);
                     let changed;
                     (distances, changed) = self.reach(color, dist, distances);
                     dist += 1;
@@ -185,12 +628,10 @@ impl<const SIZE: usize> Board<SIZE> {
                     }
                 }
 
-                //println!("{:?}", distances);
-
                 // get the minimum distance to the last column
                 distances
                     .iter()
-                    .map(|row| row[SIZE - 1])
+                    .map(|row| row[self.size - 1])
                     .filter_map(|x| match x {
                         Distance::Reachable(d) => Some(d),
                         _ => None,
@@ -201,143 +642,367 @@ impl<const SIZE: usize> Board<SIZE> {
         }
     }
 
-    pub fn get_dist_matrix(&self, color: Color) -> Vec<Vec<Distance>> {
-        let mut zero_queue = VecDeque::with_capacity(SIZE * SIZE);
+    #[cfg(test)]
+    fn reach(
+        &self,
+        player: Color,
+        distance: usize,
+        distances: Vec<Vec<Distance>>,
+    ) -> (Vec<Vec<Distance>>, bool) {
+        let mut new_distances = distances.clone();
+        let mut changed = false;
 
-        let mut distances: Vec<Vec<Distance>> = match color {
-            Color::Black => self
-                .board
-                .iter()
-                .enumerate()
-                .map(|(i, rows)| {
-                    if i == 0 {
-                        rows.iter()
-                            .enumerate()
-                            .map(|(j, value)| match value {
-                                Color::Black => {
-                                    zero_queue.push_back(Cell::new(i as i32, j as i32));
-                                    Distance::Reachable(0)
-                                }
-                                Color::White => Distance::Unreachable,
+        for i in 0..self.size {
+            for j in 0..self.size {
+                if let Distance::Reachable(d) = distances[i][j]
+                    && d == distance
+                {
+                    for neighbor in Cell::new(i as i32, j as i32).neighbors(self.size) {
+                        if let Distance::Unexplored =
+                            distances[neighbor.x as usize][neighbor.y as usize]
+                        {
+                            changed = true;
+                            match self.get(neighbor.x as usize, neighbor.y as usize) {
                                 Color::None => {
-                                    zero_queue.push_back(Cell::new(i as i32, j as i32));
-                                    Distance::Reachable(1)
+                                    new_distances[neighbor.x as usize][neighbor.y as usize] =
+                                        Distance::Reachable(distance + 1)
                                 }
-                            })
-                            .collect()
-                    } else {
-                        rows.iter()
-                            .map(|value| match value {
-                                Color::White => Distance::Unreachable,
-                                _ => Distance::Unexplored,
-                            })
-                            .collect()
-                    }
-                })
-                .collect(),
-            Color::White => self
-                .board
-                .iter()
-                .enumerate()
-                .map(|(i, rows)| {
-                    rows.iter()
-                        .enumerate()
-                        .map(|(j, value)| {
-                            if j == 0 {
-                                match value {
-                                    Color::White => {
-                                        zero_queue.push_back(Cell::new(i as i32, j as i32));
-                                        Distance::Reachable(0)
-                                    }
-                                    Color::Black => Distance::Unreachable,
-                                    Color::None => {
-                                        zero_queue.push_back(Cell::new(i as i32, j as i32));
-                                        Distance::Reachable(1)
+                                p => {
+                                    if p == player {
+                                        new_distances[neighbor.x as usize][neighbor.y as usize] =
+                                            Distance::Reachable(distance);
+                                        (new_distances, _) =
+                                            self.reach(player, distance, new_distances);
+                                    } else {
+                                        new_distances[neighbor.x as usize][neighbor.y as usize] =
+                                            Distance::Unreachable;
                                     }
                                 }
-                            } else {
-                                match value {
-                                    Color::Black => Distance::Unreachable,
-                                    _ => Distance::Unexplored,
-                                }
                             }
-                        })
-                        .collect()
-                })
-                .collect(),
-            _ => unreachable!(),
-        };
+                        }
+                    }
+                }
+            }
+        }
+
+        (new_distances, changed)
+    }
+    /// Two-distance from `color`'s first edge (top for Black, left for
+    /// White). An empty cell next to the edge is at distance 1, any other
+    /// empty cell is one more than its second closest neighbor, as the
+    /// opponent can always block the closest one. Stones of `color` are
+    /// looked through, so cells around a group are neighbors of each other.
+    ///
+    /// Stones of `color` get the best distance found around their group
+    /// (0 when it touches the edge), cells that cannot be reached this way
+    /// are `Distance::Unreachable`.
+    pub fn get_two_distance_matrix(&self, color: Color) -> Vec<Vec<Distance>> {
+        let distances = self.two_distances(color, false);
+        (0..self.size)
+            .map(|x| {
+                (0..self.size)
+                    .map(|y| match distances[Bitboard::index(self.size, x, y)] {
+                        Some(distance) => Distance::Reachable(distance),
+                        None => Distance::Unreachable,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
 
-        if zero_queue.is_empty() {
-            return distances;
+    /// Smallest sum of the two-distances to both edges over the empty
+    /// cells, the Queenbee potential. Lower is better, `None` when no cell
+    /// is reachable from both edges.
+    pub fn two_distance_potential(&self, color: Color) -> Option<usize> {
+        if self.winner() == Some(color) {
+            return Some(0);
         }
 
-        // Find all cell where Reachable without moving (Distance::Reachable(0))
-        let mut changed = true;
-        while changed {
-            changed = false;
-            let cell = zero_queue.front().unwrap();
-            for neighbor in cell.neighbors(SIZE) {
-                if self.board[neighbor.x as usize][neighbor.y as usize] == color
-                    && distances[neighbor.x as usize][neighbor.y as usize] == Distance::Unexplored
-                {
-                    zero_queue.push_back(neighbor);
-                    distances[neighbor.x as usize][neighbor.y as usize] = Distance::Reachable(0);
-                    changed = true;
+        let from_start = self.two_distances(color, false);
+        let from_end = self.two_distances(color, true);
+        self.stones(Color::None)
+            .iter()
+            .filter_map(|index| Some(from_start[index]? + from_end[index]?))
+            .min()
+    }
+
+    /// Cells along `color`'s first and second edges.
+    pub(crate) fn edges(&self, color: Color) -> (Bitboard, Bitboard) {
+        let last = self.size - 1;
+        match color {
+            Color::Black => (Bitboard::row(self.size, 0), Bitboard::row(self.size, last)),
+            _ => (
+                Bitboard::column(self.size, 0),
+                Bitboard::column(self.size, last),
+            ),
+        }
+    }
+
+    /// Two-distances from `color`'s first or second edge, by cell index,
+    /// `None` for the cells that cannot be reached.
+    fn two_distances(&self, color: Color, from_end: bool) -> [Option<usize>; NB_INDICES] {
+        let (start, end) = self.edges(color);
+        let edge = if from_end { end } else { start };
+        let empty = self.stones(Color::None);
+        let own = self.stones(color);
+
+        // Groups of `color` are looked through: the empty cells around a
+        // group are neighbors of each other.
+        let mut groups = Vec::new();
+        let mut group_of = [usize::MAX; NB_INDICES];
+        for index in own.iter() {
+            if group_of[index] != usize::MAX {
+                continue;
+            }
+            let mut stone = Bitboard::empty();
+            stone.set(index);
+            let group = stone.flood_fill(own, self.size);
+            for member in group.iter() {
+                group_of[member] = groups.len();
+            }
+            groups.push(group);
+        }
+        let liberties: Vec<Bitboard> = groups
+            .iter()
+            .map(|group| group.neighbors(self.size) & empty)
+            .collect();
+        let neighbors = |index: usize| {
+            let mut cells = Bitboard::empty();
+            for neighbor in Bitboard::neighbor_indices(self.size, index) {
+                if empty.get(neighbor) {
+                    cells.set(neighbor);
+                } else if own.get(neighbor) {
+                    cells |= liberties[group_of[neighbor]];
                 }
             }
+            cells.clear(index);
+            cells
+        };
 
-            if zero_queue.is_empty() {
-                break;
+        let mut level = empty & edge;
+        for (group, &cells) in groups.iter().zip(&liberties) {
+            if !(*group & edge).is_empty() {
+                level |= cells;
             }
         }
 
-        // Iterate through depths
-        let mut queue = zero_queue;
-        while !queue.is_empty() {
-            let cell = queue.pop_front().unwrap();
-            if let Some(depth) = distances[cell.x as usize][cell.y as usize].into() {
-                for neighbor in cell.neighbors(SIZE) {
-                    if distances[neighbor.x as usize][neighbor.y as usize] == Distance::Unexplored {
-                        if self.board[neighbor.x as usize][neighbor.y as usize] == color {
-                            queue.push_front(neighbor);
-                            distances[neighbor.x as usize][neighbor.y as usize] =
-                                Distance::Reachable(depth);
-                        } else {
-                            queue.push_back(neighbor);
-                            distances[neighbor.x as usize][neighbor.y as usize] =
-                                Distance::Reachable(depth + 1);
-                        }
+        // Cells are settled level by level: a cell gets `distance + 1` as
+        // soon as a second neighbor is settled at `distance`.
+        let mut distances = [None; NB_INDICES];
+        let mut nb_settled_neighbors = [0u8; NB_INDICES];
+        let mut settled = level;
+        let mut distance = 1;
+        while !level.is_empty() {
+            let mut next_level = Bitboard::empty();
+            for index in level.iter() {
+                distances[index] = Some(distance);
+                for neighbor in neighbors(index).and_not(settled).iter() {
+                    nb_settled_neighbors[neighbor] += 1;
+                    if nb_settled_neighbors[neighbor] == 2 {
+                        next_level.set(neighbor);
                     }
                 }
             }
+            settled |= next_level;
+            level = next_level;
+            distance += 1;
+        }
+
+        for (group, cells) in groups.into_iter().zip(liberties) {
+            let distance = if (group & edge).is_empty() {
+                cells.iter().filter_map(|index| distances[index]).min()
+            } else {
+                Some(0)
+            };
+            for member in group.iter() {
+                distances[member] = distance;
+            }
         }
 
         distances
     }
 
-    pub fn missing_move_to_win2(&self, color: Color) -> Option<usize> {
-        let distances = self.get_dist_matrix(color);
+    /// Effective resistance between `color`'s edges, seeing empty cells as
+    /// unit resistors, `color`'s stones as perfect conductors and the
+    /// opponent's stones as insulators. `Some(0.0)` once the edges are
+    /// connected, `None` when they are cut off from each other.
+    pub fn resistance(&self, color: Color) -> Option<f64> {
+        if self.winner() == Some(color) {
+            return Some(0.0);
+        }
 
-        match color {
-            Color::Black => distances
-                .iter()
-                .last()
-                .unwrap()
-                .iter()
-                .min()
-                .unwrap()
-                .to_owned()
-                .into(),
-            Color::White => distances
-                .iter()
-                .map(|row| row.iter().last().unwrap())
-                .min()
-                .unwrap()
-                .to_owned()
-                .into(),
-            _ => unreachable!(),
+        let (graph, nb_conductors) = self.connection_graph(color);
+        let mut circuit = Circuit::new(graph.len());
+        for (node, neighbors) in graph.iter().enumerate().skip(nb_conductors) {
+            // Half of each empty cell is on either side of it. Pairs of
+            // empty cells are linked once, from the first one.
+            let (conductors, cells): (Vec<usize>, Vec<usize>) =
+                neighbors.iter().partition(|&&other| other < nb_conductors);
+            for other in cells.into_iter().filter(|&other| other > node) {
+                circuit.add_resistor(node, other, 2.0);
+            }
+            for conductor in conductors {
+                circuit.add_resistor(node, conductor, 1.0);
+            }
+        }
+        circuit.effective_resistance(START, END)
+    }
+
+    /// Smallest number of empty cells the opponent has to take to cut
+    /// `color`'s edges apart, which is also the number of disjoint ways
+    /// `color` has to connect them. `None` once they are connected.
+    pub fn min_cut(&self, color: Color) -> Option<usize> {
+        if self.winner() == Some(color) {
+            return None;
+        }
+
+        // Each node is split in two, joined by an arc with the capacity of
+        // the node: one for an empty cell, unbounded for stones and edges.
+        let (graph, nb_conductors) = self.connection_graph(color);
+        let mut network = FlowNetwork::new(2 * graph.len());
+        for (node, neighbors) in graph.iter().enumerate() {
+            let capacity = if node < nb_conductors { UNBOUNDED } else { 1 };
+            network.add_arc(2 * node, 2 * node + 1, capacity);
+            for &other in neighbors {
+                network.add_arc(2 * node + 1, 2 * other, UNBOUNDED);
+            }
+        }
+        Some(network.max_flow(2 * START + 1, 2 * END))
+    }
+
+    /// Number of moves `color` is missing to connect its edges, and how many
+    /// distinct shortest ways there are to do it, counted as sequences of
+    /// empty cells and groups. `None` when the edges are cut off.
+    pub fn shortest_paths(&self, color: Color) -> Option<(usize, u64)> {
+        if self.winner() == Some(color) {
+            return Some((0, 1));
+        }
+
+        // 0-1 BFS: entering an empty cell costs a move, a group is free.
+        let (graph, nb_conductors) = self.connection_graph(color);
+        let cost = |node: usize| usize::from(node >= nb_conductors);
+        let mut distances = vec![usize::MAX; graph.len()];
+        let mut queue = VecDeque::from([START]);
+        distances[START] = 0;
+        while let Some(node) = queue.pop_front() {
+            for &other in &graph[node] {
+                let distance = distances[node] + cost(other);
+                if distance < distances[other] {
+                    distances[other] = distance;
+                    if cost(other) == 0 {
+                        queue.push_front(other);
+                    } else {
+                        queue.push_back(other);
+                    }
+                }
+            }
+        }
+        if distances[END] == usize::MAX {
+            return None;
+        }
+
+        // Groups and edges are never next to each other, so ordering by
+        // distance, then empty cells before groups, follows every shortest
+        // path.
+        let mut order: Vec<usize> = (0..graph.len())
+            .filter(|&node| distances[node] != usize::MAX)
+            .collect();
+        order.sort_by_key(|&node| (distances[node], cost(node) == 0));
+        let mut counts = vec![0u64; graph.len()];
+        counts[START] = 1;
+        for node in order {
+            for &other in &graph[node] {
+                if distances[node] + cost(other) == distances[other] {
+                    counts[other] = counts[other].saturating_add(counts[node]);
+                }
+            }
+        }
+        Some((distances[END], counts[END]))
+    }
+
+    /// `color`'s side of the board as a graph: node `START` is its first
+    /// edge, node `END` its second one, then come its groups of stones, each
+    /// merged with the edge it touches, and the empty cells. Returns the
+    /// neighbors of each node and the number of nodes before the empty
+    /// cells.
+    fn connection_graph(&self, color: Color) -> (Vec<Vec<usize>>, usize) {
+        let (start_edge, end_edge) = self.edges(color);
+
+        let mut nodes = vec![None; NB_INDICES];
+        let mut nb_nodes = 2;
+        let stones = self.stones(color);
+        for index in stones.iter() {
+            if nodes[index].is_some() {
+                continue;
+            }
+            let mut seed = Bitboard::empty();
+            seed.set(index);
+            let group = seed.flood_fill(stones, self.size);
+            let node = if !(group & start_edge).is_empty() {
+                START
+            } else if !(group & end_edge).is_empty() {
+                END
+            } else {
+                nb_nodes += 1;
+                nb_nodes - 1
+            };
+            for index in group.iter() {
+                nodes[index] = Some(node);
+            }
+        }
+        let nb_conductors = nb_nodes;
+        let empty = self.stones(Color::None);
+        for index in empty.iter() {
+            nodes[index] = Some(nb_nodes);
+            nb_nodes += 1;
+        }
+
+        let mut graph = vec![Vec::new(); nb_nodes];
+        let mut link = |a: usize, b: usize| {
+            // Edges and groups only count once, however many stones touch
+            // the cell.
+            if !graph[a].contains(&b) {
+                graph[a].push(b);
+                graph[b].push(a);
+            }
+        };
+        for index in empty.iter() {
+            let node = nodes[index].unwrap();
+            if start_edge.get(index) {
+                link(node, START);
+            }
+            if end_edge.get(index) {
+                link(node, END);
+            }
+
+            let (x, y) = Bitboard::coord(self.size, index);
+            for neighbor in Cell::new(x as i32, y as i32).neighbors(self.size) {
+                let neighbor = Bitboard::index(self.size, neighbor.x as usize, neighbor.y as usize);
+                if let Some(other) = nodes[neighbor] {
+                    link(node, other);
+                }
+            }
         }
+
+        (graph, nb_conductors)
+    }
+
+    /// Cells where the side to move has to play when the opponent's edges
+    /// are virtually connected, or one move away from it: any move outside
+    /// the carriers of these connections loses. `None` when there is no such
+    /// threat, an empty list when no move stops them all.
+    pub fn mustplay(&self) -> Option<Vec<(usize, usize)>> {
+        let opponent = VirtualConnections::new(self, self.next_color.opponent());
+        let region = opponent
+            .edge_carriers()
+            .into_iter()
+            .reduce(|region, carrier| region & carrier)?;
+        Some(
+            region
+                .iter()
+                .map(|index| Bitboard::coord(self.size, index))
+                .collect(),
+        )
     }
 
     pub fn play_random_move(&mut self) {
@@ -354,8 +1019,8 @@ impl<const SIZE: usize> Board<SIZE> {
     }
 
     #[allow(unused)]
-    pub fn random_board(nb_moves: usize) -> Board<SIZE> {
-        let mut board = Board::new();
+    pub fn random_board(size: usize, nb_moves: usize) -> Board {
+        let mut board = Board::new(size);
         let mut color = Color::Black;
 
         for _ in 0..nb_moves {
@@ -368,65 +1033,129 @@ impl<const SIZE: usize> Board<SIZE> {
 
     #[allow(unused)]
     pub fn is_win(&self) -> bool {
-        self.missing_move_to_win2(self.next_color.opponent()) == Some(0)
+        self.winner == self.next_color.opponent()
     }
 
-    #[allow(unused)]
     pub fn winner(&self) -> Option<Color> {
-        if self.winner != Color::None {
-            None
-        } else if self.missing_move_to_win2(Color::Black) == Some(0) {
-            Some(Color::Black)
-        } else if self.missing_move_to_win2(Color::White) == Some(0) {
-            Some(Color::White)
-        } else {
-            None
+        match self.winner {
+            Color::None => None,
+            winner => Some(winner),
         }
     }
 
+    /// One of the shortest chains of the winner's stones linking their two
+    /// edges, starting from the top or left edge.
+    pub fn winning_chain(&self) -> Option<Vec<(usize, usize)>> {
+        let color = self.winner()?;
+        let on_edge = |x: usize, y: usize, edge: usize| match color {
+            Color::Black => x == edge,
+            _ => y == edge,
+        };
+
+        let mut visited = vec![vec![false; self.size]; self.size];
+        let mut previous = vec![vec![None; self.size]; self.size];
+        let mut queue = VecDeque::new();
+        for index in self.stones(color).iter() {
+            let (x, y) = Bitboard::coord(self.size, index);
+            if on_edge(x, y, 0) {
+                visited[x][y] = true;
+                queue.push_back((x, y));
+            }
+        }
+
+        while let Some((x, y)) = queue.pop_front() {
+            if on_edge(x, y, self.size - 1) {
+                let mut chain = vec![(x, y)];
+                let mut cell = (x, y);
+                while let Some(before) = previous[cell.0][cell.1] {
+                    chain.push(before);
+                    cell = before;
+                }
+                chain.reverse();
+                return Some(chain);
+            }
+
+            for neighbor in Cell::new(x as i32, y as i32).neighbors(self.size) {
+                let (nx, ny) = (neighbor.x as usize, neighbor.y as usize);
+                if !visited[nx][ny] && self.get(nx, ny) == color {
+                    visited[nx][ny] = true;
+                    previous[nx][ny] = Some((x, y));
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        unreachable!("the winner's edges are connected")
+    }
+
     #[allow(unused)]
     pub fn is_full(&self) -> bool {
         self.first_possible_move().is_none()
     }
 
     pub fn possible_moves(&self) -> Vec<(usize, usize)> {
-        self.board
+        self.empty_cells().collect()
+    }
+
+    /// Empty cells in row-major order, like `possible_moves` but without
+    /// allocating. The iterator does not borrow the board, so moves can be
+    /// played and undone while going through it.
+    pub fn empty_cells(&self) -> impl Iterator<Item = (usize, usize)> + use<> {
+        let size = self.size;
+        self.stones(Color::None)
             .iter()
-            .enumerate()
-            .flat_map(|(i, row)| {
-                row.iter()
-                    .enumerate()
-                    .filter(|(_, cell)| **cell == Color::None)
-                    .map(move |(j, _)| (i, j))
-            })
-            .collect()
+            .map(move |index| Bitboard::coord(size, index))
     }
 
     pub fn first_possible_move(&self) -> Option<(usize, usize)> {
-        self.possible_moves().first().copied()
+        self.empty_cells().next()
     }
 
     #[allow(unused)]
     pub fn is_finished(&self) -> bool {
-        !self.winner().is_none()
+        self.winner().is_some()
+    }
+}
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size
+            && self.white == other.white
+            && self.black == other.black
+            && self.next_color == other.next_color
     }
 }
 
-impl<const SIZE: usize> Default for Board<SIZE> {
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.key());
+    }
+}
+
+impl Default for Board {
     fn default() -> Self {
-        Self::new()
+        Self::new(DEFAULT_BOARD_SIZE)
     }
 }
 
-impl<const SIZE: usize> fmt::Display for Board<SIZE> {
+/// The alternate form (`{:#}`) highlights the winning chain.
+impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write_column_labels(f, SIZE, 0)?;
+        let chain = if f.alternate() {
+            self.winning_chain().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        write_column_labels(f, self.size, 0)?;
 
-        for row in 0..SIZE {
-            write_row(f, self, row)?;
+        for row in 0..self.size {
+            write_highlighted_row(f, self, row, &chain)?;
         }
 
-        write_column_labels(f, SIZE, SIZE + 1)
+        write_column_labels(f, self.size, self.size + 1)
     }
 }
 
@@ -439,16 +1168,23 @@ pub fn write_column_labels(
     write!(f, " ")?;
 
     for column in 0..board_size {
-        write!(f, " {} ", column + 1)?;
+        write!(f, " ")?;
+        hex_move::write_column(f, column)?;
+        write!(f, " ")?;
     }
 
     writeln!(f)
 }
 
-pub fn write_row<const SIZE: usize>(
+pub fn write_row(f: &mut fmt::Formatter, board: &Board, row: usize) -> fmt::Result {
+    write_highlighted_row(f, board, row, &[])
+}
+
+pub fn write_highlighted_row(
     f: &mut fmt::Formatter,
-    board: &Board<SIZE>,
+    board: &Board,
     row: usize,
+    highlighted: &[(usize, usize)],
 ) -> fmt::Result {
     write_indent(f, row)?;
     write!(f, "{:2}\\", row + 1)?;
@@ -457,7 +1193,12 @@ pub fn write_row<const SIZE: usize>(
         if column > 0 {
             write!(f, "  ")?;
         }
-        write!(f, "{}", board.get_board()[row][column].to_char())?;
+        let color = board.get(row, column);
+        if highlighted.contains(&(row, column)) {
+            write!(f, "{}", color.to_highlighted_char())?;
+        } else {
+            write!(f, "{}", color.to_char())?;
+        }
     }
 
     writeln!(f, "\\{:2}", row + 1)
@@ -474,7 +1215,7 @@ mod tests {
 
     #[test]
     fn test_board() {
-        let mut board = Board::<2>::new();
+        let mut board = Board::new(2);
         board.play(0, 0);
         board.play(0, 1);
         board.play(1, 1);
@@ -486,7 +1227,7 @@ mod tests {
 
     #[test]
     fn is_game_over() {
-        let board = Board::<4>::new();
+        let board = Board::new(4);
         assert_eq!(board.missing_move_to_win(Color::White), Some(4));
 
         assert!(!board.is_win());
@@ -494,7 +1235,7 @@ mod tests {
 
     #[test]
     fn first_possible_move() {
-        let mut board = Board::<2>::new();
+        let mut board = Board::new(2);
         board.play(0, 0);
 
         assert_eq!(board.first_possible_move().unwrap(), (0, 1));
@@ -502,7 +1243,7 @@ mod tests {
 
     #[test]
     fn is_game_over1() {
-        let mut board = Board::<4>::new();
+        let mut board = Board::new(4);
         board.play(3, 0);
         board.play(0, 0);
         board.play(3, 1);
@@ -520,7 +1261,7 @@ mod tests {
 
     #[test]
     fn is_game_over2() {
-        let mut board = Board::<4>::new();
+        let mut board = Board::new(4);
         board.play(3, 0);
         board.play(0, 0);
         board.play(3, 1);
@@ -536,12 +1277,12 @@ mod tests {
 
     #[test]
     fn missing_moves() {
-        let board = Board::<4>::new();
+        let board = Board::new(4);
 
         assert_eq!(board.missing_move_to_win(Color::White), Some(4));
         assert_eq!(board.missing_move_to_win(Color::Black), Some(4));
 
-        let mut board = Board::<4>::new();
+        let mut board = Board::new(4);
         board.play(3, 0);
         board.play(0, 0);
         board.play(3, 1);
@@ -552,7 +1293,7 @@ mod tests {
 
     #[test]
     fn dist_matrix() {
-        let mut board = Board::<2>::new();
+        let mut board = Board::new(2);
         board.play(0, 0);
 
         let dist_matrix_white = board.get_dist_matrix(Color::White);
@@ -576,7 +1317,7 @@ mod tests {
 
     #[test]
     fn missing_moves_to_win2() {
-        let mut board = Board::<4>::new();
+        let mut board = Board::new(4);
 
         assert_eq!(board.missing_move_to_win2(Color::White), Some(4));
         assert_eq!(board.missing_move_to_win2(Color::Black), Some(4));
@@ -589,11 +1330,421 @@ mod tests {
         assert_eq!(board.missing_move_to_win2(Color::Black), Some(4));
     }
 
+    #[test]
+    fn missing_moves_reach() {
+        let mut board = Board::new(4);
+
+        assert_eq!(board.missing_move_to_win_reach(Color::White), Some(4));
+        assert_eq!(board.missing_move_to_win_reach(Color::Black), Some(4));
+
+        board.play(3, 0);
+        board.play(0, 0);
+        board.play(3, 1);
+
+        assert_eq!(board.missing_move_to_win_reach(Color::White), Some(2));
+        assert_eq!(board.missing_move_to_win_reach(Color::Black), Some(4));
+
+        board.play(0, 1);
+        board.play(2, 2);
+        board.play(1, 0);
+        board.play(1, 3);
+
+        assert_eq!(board.missing_move_to_win_reach(Color::White), Some(0));
+    }
+
     #[test]
     fn possible_moves() {
-        let mut board = Board::<2>::new();
+        let mut board = Board::new(2);
         board.play(0, 0);
 
         assert_eq!(board.possible_moves(), vec![(0, 1), (1, 0), (1, 1)]);
     }
+
+    #[test]
+    fn winner() {
+        let mut board = Board::new(7);
+        let black = [(0, 5), (1, 4), (2, 3), (3, 3), (4, 3), (5, 3), (6, 3)];
+        let white = [(2, 4), (2, 5), (3, 4), (3, 6), (4, 5), (5, 4), (5, 6)];
+        for (w, b) in white.iter().zip(black.iter()) {
+            assert_eq!(board.winner(), None);
+            board.play(w.0, w.1);
+            board.play(b.0, b.1);
+        }
+
+        assert!(board.is_connected(Color::Black));
+        assert!(!board.is_connected(Color::White));
+        assert_eq!(board.winner(), Some(Color::Black));
+        assert!(board.is_win());
+    }
+
+    #[test]
+    fn winner_matches_flood_fill() {
+        for nb_moves in 0..=49 {
+            let board = Board::random_board(7, nb_moves);
+            let expected = [Color::Black, Color::White]
+                .into_iter()
+                .find(|color| board.is_connected(*color));
+
+            assert_eq!(board.winner(), expected, "{}", board);
+        }
+    }
+
+    #[test]
+    fn key() {
+        let mut board = Board::new(4);
+        let empty_key = board.key();
+        assert_ne!(empty_key, Board::new(5).key());
+
+        board.play(0, 0);
+        board.play(1, 1);
+        board.play(2, 2);
+
+        let mut transposed = Board::new(4);
+        transposed.play(2, 2);
+        transposed.play(1, 1);
+        transposed.play(0, 0);
+        assert_eq!(board.key(), transposed.key());
+
+        let mut swapped_colors = Board::new(4);
+        swapped_colors.play(1, 1);
+        swapped_colors.play(0, 0);
+        swapped_colors.play(2, 2);
+        assert_ne!(board.key(), swapped_colors.key());
+
+        assert_eq!(Board::new(7).key(), 0xa79e_0f09_851f_0af4);
+    }
+
+    #[test]
+    fn undo() {
+        let mut board = Board::new(3);
+        assert_eq!(board.undo(), None);
+
+        let moves = [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)];
+        for (x, y) in moves {
+            board.play(x, y);
+        }
+        assert_eq!(board.moves(), moves.map(|(x, y)| Move::Cell(x, y)));
+        assert_eq!(board.winner(), Some(Color::White));
+
+        assert_eq!(board.undo(), Some(Move::Cell(0, 2)));
+        assert_eq!(board.winner(), None);
+        assert_eq!(board.next_color(), Color::White);
+        assert!(board.is_valid(0, 2));
+
+        let mut expected = Board::new(3);
+        for (x, y) in &moves[..4] {
+            expected.play(*x, *y);
+        }
+        assert_eq!(board, expected);
+        assert_eq!(board.key(), expected.key());
+
+        while board.undo().is_some() {}
+        assert_eq!(board, Board::new(3));
+        assert_eq!(board.key(), Board::new(3).key());
+        assert!(board.moves().is_empty());
+    }
+
+    #[test]
+    fn try_play() {
+        let mut board = Board::new(2);
+        assert_eq!(board.try_play(0, 0), Ok(()));
+        assert_eq!(board.try_play(0, 0), Err(HexError::Occupied { x: 0, y: 0 }));
+        assert_eq!(
+            board.try_play(2, 0),
+            Err(HexError::OutOfBounds { x: 2, y: 0 })
+        );
+        assert_eq!(
+            board.try_play_as(Color::White, 1, 1),
+            Err(HexError::WrongColor {
+                expected: Color::Black,
+                found: Color::White
+            })
+        );
+        assert_eq!(board.moves(), &[Move::Cell(0, 0)]);
+
+        assert_eq!(board.try_play_as(Color::Black, 1, 0), Ok(()));
+        assert_eq!(board.try_play(0, 1), Ok(()));
+        assert_eq!(board.try_play(1, 1), Err(HexError::GameFinished));
+        assert_eq!(board.next_color(), Color::Black);
+    }
+
+    #[test]
+    fn swap() {
+        let mut board = Board::new(3);
+        board.play(0, 1);
+        assert!(!board.can_swap());
+        assert_eq!(board.swap(), Err(HexError::SwapNotAllowed));
+
+        board.set_swap_rule(true);
+        assert!(board.can_swap());
+        let before_swap = board.clone();
+
+        assert_eq!(board.swap(), Ok(()));
+        assert_eq!(board.get(0, 1), Color::None);
+        assert_eq!(board.get(1, 0), Color::Black);
+        assert_eq!(board.next_color(), Color::White);
+        assert_eq!(board.moves(), &[Move::Cell(0, 1), Move::Swap]);
+        assert!(!board.can_swap());
+
+        board.play(2, 2);
+        assert_eq!(board.swap(), Err(HexError::SwapNotAllowed));
+
+        assert_eq!(board.undo(), Some(Move::Cell(2, 2)));
+        assert_eq!(board.undo(), Some(Move::Swap));
+        assert_eq!(board, before_swap);
+        assert_eq!(board.key(), before_swap.key());
+    }
+
+    #[test]
+    fn sizes() {
+        for size in [MIN_BOARD_SIZE, 11, 13, MAX_BOARD_SIZE] {
+            let board = Board::random_board(size, size * size);
+            assert_eq!(board.size(), size);
+            assert!(board.is_full());
+            assert!(board.winner().is_some(), "{}", board);
+        }
+    }
+
+    #[test]
+    fn winning_chain() {
+        let mut board = Board::new(4);
+        assert_eq!(board.winning_chain(), None);
+
+        // White wins with a3 b2 c2 d2, a1 and b1 are not needed.
+        for (x, y) in [
+            (0, 0),
+            (3, 3),
+            (0, 1),
+            (3, 2),
+            (2, 0),
+            (3, 1),
+            (1, 1),
+            (2, 3),
+            (1, 2),
+        ] {
+            board.play(x, y);
+        }
+        assert_eq!(board.winner(), None);
+        board.play(3, 0);
+        board.play(1, 3);
+
+        assert_eq!(board.winner(), Some(Color::White));
+        assert_eq!(
+            board.winning_chain(),
+            Some(vec![(2, 0), (1, 1), (1, 2), (1, 3)])
+        );
+
+        let highlighted = format!("{:#}", board);
+        assert_eq!(highlighted.matches('◆').count(), 4);
+        assert_eq!(highlighted.matches('●').count(), 2);
+        assert!(!format!("{}", board).contains('◆'));
+    }
+
+    #[test]
+    fn two_distance() {
+        let board = Board::new(3);
+        let expected = [[1, 1, 1], [2, 2, 3], [3, 4, 5]].map(|row| row.map(Distance::Reachable));
+        assert_eq!(board.get_two_distance_matrix(Color::Black), expected);
+        assert_eq!(board.two_distance_potential(Color::Black), Some(4));
+        assert_eq!(board.two_distance_potential(Color::White), Some(4));
+
+        // Black's b1 touches the top edge, so a2 and b2 are at distance 1.
+        let mut board = Board::new(3);
+        board.play(2, 2);
+        board.play(0, 1);
+        let distances = board.get_two_distance_matrix(Color::Black);
+        assert_eq!(distances[0][1], Distance::Reachable(0));
+        assert_eq!(distances[1][0], Distance::Reachable(1));
+        assert_eq!(distances[1][1], Distance::Reachable(1));
+        assert_eq!(distances[2][2], Distance::Unreachable);
+        assert!(
+            board.two_distance_potential(Color::Black) < board.two_distance_potential(Color::White)
+        );
+    }
+
+    #[test]
+    fn resistance() {
+        // Solved by hand with the 180° symmetry: 12/7.
+        let board = Board::new(2);
+        let resistance = board.resistance(Color::Black).unwrap();
+        assert!((resistance - 12.0 / 7.0).abs() < 1e-9, "{}", resistance);
+        assert_eq!(
+            board.resistance(Color::White),
+            board.resistance(Color::Black)
+        );
+
+        let mut board = Board::new(5);
+        let empty = board.resistance(Color::White).unwrap();
+        board.play(2, 2);
+        assert!(board.resistance(Color::White).unwrap() < empty);
+        assert!(board.resistance(Color::Black).unwrap() > empty);
+
+        // Black walls off White's left edge.
+        let mut board = Board::new(3);
+        for (x, y) in [(2, 2), (0, 0), (2, 1), (1, 0), (1, 2)] {
+            board.play(x, y);
+        }
+        board.play(2, 0);
+        assert_eq!(board.winner(), Some(Color::Black));
+        assert_eq!(board.resistance(Color::Black), Some(0.0));
+        assert_eq!(board.resistance(Color::White), None);
+    }
+
+    #[test]
+    fn min_cut_and_shortest_paths() {
+        let board = Board::new(3);
+        assert_eq!(board.min_cut(Color::White), Some(3));
+        assert_eq!(board.shortest_paths(Color::Black), Some((3, 8)));
+        assert_eq!(Board::new(2).shortest_paths(Color::White), Some((2, 3)));
+
+        // Black walls off all but the bottom row: every path goes through b4.
+        let mut board = Board::new(4);
+        for x in 0..3 {
+            board.place_stone(Color::Black, x, 1);
+        }
+        assert_eq!(board.min_cut(Color::White), Some(1));
+        assert_eq!(board.shortest_paths(Color::White), Some((4, 4)));
+
+        // A group is crossed for free, and counted once.
+        let mut board = Board::new(3);
+        board.play(1, 1);
+        assert_eq!(board.shortest_paths(Color::White), Some((2, 4)));
+        assert_eq!(board.min_cut(Color::White), Some(3));
+
+        board.play(0, 0);
+        board.play(1, 0);
+        board.play(0, 1);
+        board.play(1, 2);
+        assert_eq!(board.winner(), Some(Color::White));
+        assert_eq!(board.min_cut(Color::White), None);
+        assert_eq!(board.shortest_paths(Color::White), Some((0, 1)));
+        assert_eq!(board.min_cut(Color::Black), Some(0));
+        assert_eq!(board.shortest_paths(Color::Black), None);
+    }
+
+    #[test]
+    fn groups() {
+        assert_eq!(Board::new(3).groups(Color::White), vec![]);
+
+        let mut board = Board::new(4);
+        for (x, y) in [(0, 0), (1, 1), (1, 0), (3, 3), (2, 2)] {
+            board.play(x, y);
+        }
+        let white = board.groups(Color::White);
+        assert_eq!(white.len(), 2);
+        assert_eq!(white[0].cells, vec![(0, 0), (1, 0)]);
+        assert_eq!(white[0].liberties, vec![(0, 1), (2, 0)]);
+        assert_eq!(white[0].edges, vec![Edge::Top, Edge::Left]);
+        assert!(!white[0].connects());
+        assert_eq!(white[1].cells, vec![(2, 2)]);
+        assert_eq!(
+            white[1].liberties,
+            vec![(1, 2), (1, 3), (2, 1), (2, 3), (3, 1), (3, 2)]
+        );
+
+        let black = board.groups(Color::Black);
+        assert_eq!(black.len(), 2);
+        assert_eq!(black[0].cells, vec![(1, 1)]);
+        assert_eq!(black[1].edges, vec![Edge::Bottom, Edge::Right]);
+        assert!(black[1].touches(Edge::Bottom));
+    }
+
+    #[test]
+    fn mustplay() {
+        assert_eq!(Board::new(4).mustplay(), None);
+
+        // Black's b2 reaches the top with a bridge and threatens b3 to join
+        // b4 on the bottom edge: White has to play in between.
+        let mut board = Board::new(4);
+        board.place_stone(Color::Black, 1, 1);
+        board.place_stone(Color::Black, 3, 1);
+        let mustplay = board.mustplay().unwrap();
+        assert!(mustplay.contains(&(2, 1)));
+        assert!(
+            mustplay
+                .iter()
+                .all(|cell| [(0, 1), (0, 2), (2, 1)].contains(cell))
+        );
+
+        // With the bridge on the other side as well, nothing saves White.
+        board.place_stone(Color::Black, 2, 2);
+        assert_eq!(board.mustplay(), Some(vec![]));
+    }
+
+    #[test]
+    fn symmetries() {
+        let mut board = Board::new(5);
+        for (x, y) in [(0, 1), (2, 2), (1, 3), (4, 0)] {
+            board.play(x, y);
+        }
+
+        let rotated = board.rotate180();
+        assert_eq!(rotated.get(4, 3), Color::White);
+        assert_eq!(rotated.get(0, 4), Color::Black);
+        assert_eq!(rotated.next_color(), Color::White);
+        assert_eq!(rotated.moves()[0], Move::Cell(4, 3));
+        assert_eq!(rotated.rotate180(), board);
+
+        let swapped = board.transpose_swap();
+        assert_eq!(swapped.get(1, 0), Color::Black);
+        assert_eq!(swapped.get(0, 4), Color::White);
+        assert_eq!(swapped.next_color(), Color::Black);
+        assert!(swapped.moves().is_empty());
+        assert_eq!(swapped.transpose_swap(), board);
+
+        for symmetry in Symmetry::ALL {
+            let transformed = board.transform(symmetry);
+            assert_eq!(transformed.key(), board.transformed_key(symmetry));
+            assert_eq!(transformed.canonical_key().0, board.canonical_key().0);
+            assert_eq!(transformed.canonical().0, board.canonical().0);
+        }
+
+        // The keys of the transforms follow undo and swap as well.
+        board.undo();
+        let mut swapped = Board::new(5);
+        swapped.set_swap_rule(true);
+        swapped.play(0, 1);
+        swapped.swap().unwrap();
+        for board in [board, swapped] {
+            for symmetry in Symmetry::ALL {
+                assert_eq!(
+                    board.transform(symmetry).key(),
+                    board.transformed_key(symmetry)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_winner() {
+        let mut board = Board::new(3);
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            board.play(x, y);
+        }
+        assert_eq!(board.winner(), Some(Color::White));
+
+        assert_eq!(board.rotate180().winner(), Some(Color::White));
+        assert_eq!(board.transpose_swap().winner(), Some(Color::Black));
+        assert_eq!(
+            board.transpose_swap().missing_move_to_win(Color::Black),
+            board.missing_move_to_win(Color::White)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_large() {
+        Board::new(MAX_BOARD_SIZE + 1);
+    }
+
+    #[test]
+    fn try_new() {
+        assert_eq!(
+            Board::try_new(MIN_BOARD_SIZE).map(|board| board.size()),
+            Ok(MIN_BOARD_SIZE)
+        );
+        for size in [0, MIN_BOARD_SIZE - 1, MAX_BOARD_SIZE + 1] {
+            assert_eq!(Board::try_new(size), Err(HexError::InvalidSize { size }));
+        }
+    }
 }
@@ -28,24 +28,20 @@ impl Cell {
         0 <= self.x && self.x < size as i32 && 0 <= self.y && self.y < size as i32
     }
 
-    pub fn neighbors(&self, board_size: usize) -> Vec<Cell> {
-        let steps = [
-            Cell::new(-1, 0),
-            Cell::new(-1, 1),
-            Cell::new(0, -1),
-            Cell::new(0, 1),
-            Cell::new(1, -1),
-            Cell::new(1, 0),
-        ];
-
-        steps
+    pub fn neighbors(&self, board_size: usize) -> impl Iterator<Item = Cell> {
+        let cell = *self;
+        NEIGHBOR_STEPS
             .iter()
-            .map(|step| *step + *self)
-            .filter(|new_cell| new_cell.is_on_board(board_size))
-            .collect()
+            .map(move |&(x, y)| Cell::new(x, y) + cell)
+            .filter(move |new_cell| new_cell.is_on_board(board_size))
     }
 }
 
+/// Steps to the six neighbors of a cell, in the order `Cell::neighbors`
+/// returns them.
+pub(crate) const NEIGHBOR_STEPS: [(i32, i32); 6] =
+    [(-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0)];
+
 //test
 #[cfg(test)]
 mod tests {
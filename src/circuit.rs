@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+
+/// A resistor network, solved with conjugate gradient on its sparse
+/// Laplacian. Hex positions give a few hundred nodes with at most six
+/// resistors each, where a dense solver would be wasteful.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    /// For each node, its neighbors and the conductance of the resistor.
+    conductances: Vec<Vec<(usize, f64)>>,
+}
+
+const TOLERANCE: f64 = 1e-10;
+
+impl Circuit {
+    pub fn new(nb_nodes: usize) -> Circuit {
+        Circuit {
+            conductances: vec![Vec::new(); nb_nodes],
+        }
+    }
+
+    pub fn add_resistor(&mut self, a: usize, b: usize, resistance: f64) {
+        debug_assert!(resistance > 0.0 && a != b);
+        self.conductances[a].push((b, 1.0 / resistance));
+        self.conductances[b].push((a, 1.0 / resistance));
+    }
+
+    /// Resistance seen between `source` and `sink`, `None` when no current
+    /// can flow between them.
+    pub fn effective_resistance(&self, source: usize, sink: usize) -> Option<f64> {
+        // Only the part of the network connected to the source carries
+        // current, and leaving the rest out keeps the system non-singular.
+        let mut unknowns = vec![None; self.conductances.len()];
+        let mut nodes = Vec::new();
+        let mut reached = vec![false; self.conductances.len()];
+        let mut queue = VecDeque::from([source]);
+        reached[source] = true;
+        while let Some(node) = queue.pop_front() {
+            for &(neighbor, _) in &self.conductances[node] {
+                if !reached[neighbor] {
+                    reached[neighbor] = true;
+                    if neighbor != sink {
+                        unknowns[neighbor] = Some(nodes.len());
+                        nodes.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+        if !reached[sink] {
+            return None;
+        }
+
+        // Kirchhoff's current law at every other node, with the source held
+        // at potential 1 and the sink at 0.
+        let mut rhs = vec![0.0; nodes.len()];
+        for (i, &node) in nodes.iter().enumerate() {
+            for &(neighbor, conductance) in &self.conductances[node] {
+                if neighbor == source {
+                    rhs[i] += conductance;
+                }
+            }
+        }
+        let multiply = |vector: &[f64], result: &mut [f64]| {
+            for (i, &node) in nodes.iter().enumerate() {
+                result[i] = 0.0;
+                for &(neighbor, conductance) in &self.conductances[node] {
+                    result[i] += conductance * vector[i];
+                    if let Some(j) = unknowns[neighbor] {
+                        result[i] -= conductance * vector[j];
+                    }
+                }
+            }
+        };
+        let potentials = conjugate_gradient(multiply, &rhs);
+
+        let current: f64 = self.conductances[source]
+            .iter()
+            .map(|&(neighbor, conductance)| {
+                let potential = unknowns[neighbor].map_or(0.0, |i| potentials[i]);
+                conductance * (1.0 - potential)
+            })
+            .sum();
+        Some(1.0 / current)
+    }
+}
+
+fn conjugate_gradient(multiply: impl Fn(&[f64], &mut [f64]), rhs: &[f64]) -> Vec<f64> {
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| a * b).sum::<f64>();
+
+    let mut solution = vec![0.0; rhs.len()];
+    let mut residual = rhs.to_vec();
+    let mut direction = residual.clone();
+    let mut product = vec![0.0; rhs.len()];
+    let mut residual_norm = dot(&residual, &residual);
+    let threshold = TOLERANCE * TOLERANCE * residual_norm.max(1.0);
+
+    for _ in 0..rhs.len() * 4 {
+        if residual_norm <= threshold {
+            break;
+        }
+        multiply(&direction, &mut product);
+        let step = residual_norm / dot(&direction, &product);
+        for i in 0..rhs.len() {
+            solution[i] += step * direction[i];
+            residual[i] -= step * product[i];
+        }
+
+        let next_norm = dot(&residual, &residual);
+        for i in 0..rhs.len() {
+            direction[i] = residual[i] + next_norm / residual_norm * direction[i];
+        }
+        residual_norm = next_norm;
+    }
+
+    solution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: Option<f64>, expected: f64) {
+        assert!((value.unwrap() - expected).abs() < 1e-9, "{:?}", value);
+    }
+
+    #[test]
+    fn series_and_parallel() {
+        let mut circuit = Circuit::new(4);
+        circuit.add_resistor(0, 1, 1.0);
+        circuit.add_resistor(1, 3, 2.0);
+        assert_close(circuit.effective_resistance(0, 3), 3.0);
+
+        circuit.add_resistor(0, 2, 3.0);
+        circuit.add_resistor(2, 3, 3.0);
+        assert_close(circuit.effective_resistance(0, 3), 2.0);
+    }
+
+    #[test]
+    fn bridge() {
+        // Balanced Wheatstone bridge, no current through the middle.
+        let mut circuit = Circuit::new(4);
+        circuit.add_resistor(0, 1, 1.0);
+        circuit.add_resistor(0, 2, 2.0);
+        circuit.add_resistor(1, 3, 1.0);
+        circuit.add_resistor(2, 3, 2.0);
+        circuit.add_resistor(1, 2, 5.0);
+        assert_close(circuit.effective_resistance(0, 3), 4.0 / 3.0);
+    }
+
+    #[test]
+    fn disconnected() {
+        let mut circuit = Circuit::new(5);
+        circuit.add_resistor(0, 1, 1.0);
+        circuit.add_resistor(2, 3, 1.0);
+        circuit.add_resistor(3, 4, 1.0);
+        assert_eq!(circuit.effective_resistance(0, 3), None);
+
+        // A dangling branch does not change anything.
+        circuit.add_resistor(1, 3, 1.0);
+        assert_close(circuit.effective_resistance(0, 3), 2.0);
+    }
+}
@@ -2,6 +2,11 @@ use crate::score::Score;
 use std::fmt;
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Color {
     White,
     Black,
@@ -25,6 +30,14 @@ impl Color {
         }
     }
 
+    pub fn to_highlighted_char(self) -> char {
+        match self {
+            Color::Black => '◇',
+            Color::White => '◆',
+            Color::None => '*',
+        }
+    }
+
     pub fn win_score(&self) -> Score {
         match self {
             Color::White => Score::WhiteCheckMate,
@@ -46,7 +59,7 @@ impl Iterator for Color {
         if *self == Color::None {
             None
         } else {
-            Some(self.clone())
+            Some(*self)
         }
     }
 }
@@ -0,0 +1,311 @@
+//! Reading back the board diagrams printed by `Display`:
+//!
+//! ```text
+//!   a  b  c
+//!  1\●  .  .\ 1
+//!   2\.  ○  .\ 2
+//!    3\.  .  .\ 3
+//!        a  b  c
+//! ```
+//!
+//! The ASCII variant writes White's stones as `W` and Black's as `B`,
+//! either case. The highlighted stones of the alternate form are read as
+//! plain ones. Column labels and blank lines are skipped, and the row
+//! label after the cells may be left out.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::{Board, MAX_BOARD_SIZE, MIN_BOARD_SIZE};
+use crate::color::Color;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseDiagramError {
+    /// No row of cells was found.
+    Empty,
+    /// The number of rows is not a supported board size.
+    InvalidSize(usize),
+    /// Line `line` (1-based) is neither a row nor column labels.
+    UnexpectedLine {
+        line: usize,
+    },
+    /// The row on line `line` is labelled `found` instead of `expected`.
+    RowLabel {
+        line: usize,
+        expected: usize,
+        found: String,
+    },
+    /// Row `row` holds `found` cells instead of `expected`.
+    RowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidCell {
+        row: usize,
+        found: String,
+    },
+    /// The stone counts do not come from alternating moves.
+    StoneCounts {
+        white: usize,
+        black: usize,
+    },
+}
+
+impl Board {
+    /// Reads a diagram printed by `Display`, or its ASCII variant, as
+    /// described in the [`diagram`](crate::diagram) module. The side to move
+    /// comes from the stone counts: White when they are equal, Black when
+    /// White has one more stone, and White again when Black has one more,
+    /// after a swap. The board has no history.
+    pub fn from_diagram(text: &str) -> Result<Board, ParseDiagramError> {
+        let mut rows: Vec<Vec<Color>> = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let is_labels = line
+                .split_whitespace()
+                .all(|label| label.chars().all(|c| c.is_ascii_lowercase()));
+            if is_labels {
+                continue;
+            }
+
+            let (label, rest) = line
+                .split_once('\\')
+                .ok_or(ParseDiagramError::UnexpectedLine { line: number + 1 })?;
+            let (cells, end_label) = rest
+                .rsplit_once('\\')
+                .ok_or(ParseDiagramError::UnexpectedLine { line: number + 1 })?;
+            let expected = (rows.len() + 1).to_string();
+            let (label, end_label) = (label.trim(), end_label.trim());
+            let wrong_label = if label != expected {
+                Some(label)
+            } else if !end_label.is_empty() && end_label != expected {
+                Some(end_label)
+            } else {
+                None
+            };
+            if let Some(found) = wrong_label {
+                return Err(ParseDiagramError::RowLabel {
+                    line: number + 1,
+                    expected: rows.len() + 1,
+                    found: found.to_string(),
+                });
+            }
+
+            let row = cells
+                .split_whitespace()
+                .map(|cell| {
+                    parse_cell(cell).ok_or_else(|| ParseDiagramError::InvalidCell {
+                        row: rows.len() + 1,
+                        found: cell.to_string(),
+                    })
+                })
+                .collect::<Result<Vec<Color>, ParseDiagramError>>()?;
+            rows.push(row);
+        }
+
+        let size = rows.len();
+        if size == 0 {
+            return Err(ParseDiagramError::Empty);
+        }
+        if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) {
+            return Err(ParseDiagramError::InvalidSize(size));
+        }
+
+        let mut board = Board::new(size);
+        for (x, row) in rows.iter().enumerate() {
+            if row.len() != size {
+                return Err(ParseDiagramError::RowLength {
+                    row: x + 1,
+                    expected: size,
+                    found: row.len(),
+                });
+            }
+            for (y, &color) in row.iter().enumerate() {
+                if color != Color::None {
+                    board.place_stone(color, x, y);
+                }
+            }
+        }
+
+        let white = board.stones(Color::White).count();
+        let black = board.stones(Color::Black).count();
+        let next_color = if white == black || black == white + 1 {
+            Color::White
+        } else if white == black + 1 {
+            Color::Black
+        } else {
+            return Err(ParseDiagramError::StoneCounts { white, black });
+        };
+        board.set_next_color(next_color);
+        Ok(board)
+    }
+}
+
+fn parse_cell(cell: &str) -> Option<Color> {
+    let mut chars = cell.chars();
+    let color = match chars.next()? {
+        '●' | '◆' | 'W' | 'w' => Color::White,
+        '○' | '◇' | 'B' | 'b' => Color::Black,
+        '.' | '*' => Color::None,
+        _ => return None,
+    };
+    chars.next().is_none().then_some(color)
+}
+
+/// The inverse of `Display`, see [`Board::from_diagram`].
+impl FromStr for Board {
+    type Err = ParseDiagramError;
+
+    fn from_str(text: &str) -> Result<Board, ParseDiagramError> {
+        Board::from_diagram(text)
+    }
+}
+
+impl fmt::Display for ParseDiagramError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseDiagramError::Empty => write!(f, "the diagram has no rows"),
+            ParseDiagramError::InvalidSize(size) => write!(
+                f,
+                "{} rows, a board has between {} and {}",
+                size, MIN_BOARD_SIZE, MAX_BOARD_SIZE
+            ),
+            ParseDiagramError::UnexpectedLine { line } => write!(
+                f,
+                "line {} is neither a row between backslashes nor column labels",
+                line
+            ),
+            ParseDiagramError::RowLabel {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} is labelled '{}' instead of row {}",
+                line, found, expected
+            ),
+            ParseDiagramError::RowLength {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {} has {} cells instead of {}", row, found, expected),
+            ParseDiagramError::InvalidCell { row, found } => {
+                write!(f, "'{}' in row {}, expected ●, ○, W, B or .", found, row)
+            }
+            ParseDiagramError::StoneCounts { white, black } => write!(
+                f,
+                "{} White and {} Black stones cannot come from alternating moves",
+                white, black
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseDiagramError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for size in [MIN_BOARD_SIZE, 7, 11, MAX_BOARD_SIZE] {
+            for nb_moves in [0, 1, size, size * size / 2] {
+                let board = Board::random_board(size, nb_moves);
+                assert_eq!(board.to_string().parse::<Board>(), Ok(board.clone()));
+                assert_eq!(Board::from_diagram(&format!("{:#}", board)), Ok(board));
+            }
+        }
+    }
+
+    #[test]
+    fn ascii() {
+        let diagram = "
+            1\\W  .  .\\
+             2\\.  b  .\\
+              3\\.  .  w\\
+        ";
+        let board = Board::from_diagram(diagram).unwrap();
+        assert_eq!(board.size(), 3);
+        assert_eq!(board.get(0, 0), Color::White);
+        assert_eq!(board.get(1, 1), Color::Black);
+        assert_eq!(board.get(2, 2), Color::White);
+        assert_eq!(board.next_color(), Color::Black);
+        assert!(board.moves().is_empty());
+    }
+
+    #[test]
+    fn side_to_move() {
+        let swapped = Board::from_diagram("1\\.  B\\ 1\n2\\.  .\\ 2").unwrap();
+        assert_eq!(swapped.next_color(), Color::White);
+        let board = Board::from_diagram("1\\W  B\\ 1\n2\\.  .\\ 2").unwrap();
+        assert_eq!(board.next_color(), Color::White);
+    }
+
+    #[test]
+    fn errors() {
+        use ParseDiagramError::*;
+
+        let cases = [
+            ("", Empty),
+            ("  a  b\n", Empty),
+            ("1\\.\\ 1", InvalidSize(1)),
+            ("1\\.  .\\ 1\nWhite to move", UnexpectedLine { line: 2 }),
+            ("1\\.  .\\ 1\n2\\.  .", UnexpectedLine { line: 2 }),
+            (
+                "1\\.  .\\ 1\n3\\.  .\\ 3",
+                RowLabel {
+                    line: 2,
+                    expected: 2,
+                    found: "3".to_string(),
+                },
+            ),
+            (
+                "1\\.  .\\ 1\n2\\.  .\\ 1",
+                RowLabel {
+                    line: 2,
+                    expected: 2,
+                    found: "1".to_string(),
+                },
+            ),
+            (
+                "1\\.  .\\ 1\n2\\.\\ 2",
+                RowLength {
+                    row: 2,
+                    expected: 2,
+                    found: 1,
+                },
+            ),
+            (
+                "1\\.  x\\ 1\n2\\.  .\\ 2",
+                InvalidCell {
+                    row: 1,
+                    found: "x".to_string(),
+                },
+            ),
+            (
+                "1\\.  ..\\ 1\n2\\.  .\\ 2",
+                InvalidCell {
+                    row: 1,
+                    found: "..".to_string(),
+                },
+            ),
+            (
+                "1\\W  W\\ 1\n2\\.  .\\ 2",
+                StoneCounts { white: 2, black: 0 },
+            ),
+        ];
+        for (input, error) in cases {
+            assert_eq!(Board::from_diagram(input), Err(error), "{:?}", input);
+        }
+
+        assert_eq!(
+            "1\\.  .\\ 1\n2\\.\\ 2"
+                .parse::<Board>()
+                .unwrap_err()
+                .to_string(),
+            "row 2 has 1 cells instead of 2"
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Randomized differential checks of the distance and win detection code
+//! against distances rebuilt from scratch and simple, independent
+//! implementations. A failing position is shrunk to a minimal sequence of
+//! steps before being reported.
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{
+    board::{Board, MAX_BOARD_SIZE, MIN_BOARD_SIZE},
+    cell::Cell,
+    color::Color,
+    distance_map::DistanceMap,
+    hex_move::Move,
+};
+
+/// What a test case does to a fresh board, in order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    Play(usize, usize),
+    Undo,
+}
+
+#[derive(Debug, Clone)]
+struct Case {
+    size: usize,
+    steps: Vec<Step>,
+}
+
+impl Case {
+    fn random(rng: &mut StdRng, size: usize) -> Case {
+        let mut board = Board::new(size);
+        let mut steps = Vec::new();
+        let nb_steps = rng.random_range(0..=size * size + size);
+        for _ in 0..nb_steps {
+            if !board.moves().is_empty() && rng.random_bool(0.15) {
+                board.undo();
+                steps.push(Step::Undo);
+            } else if board.winner().is_none() {
+                let moves = board.possible_moves();
+                let (x, y) = moves[rng.random_range(0..moves.len())];
+                board.play(x, y);
+                steps.push(Step::Play(x, y));
+            }
+        }
+        Case { size, steps }
+    }
+
+    /// Boards after each step, `None` when a step is illegal, which happens
+    /// to the cases tried while shrinking.
+    fn replay(&self) -> Option<Vec<Board>> {
+        let mut board = Board::new(self.size);
+        let mut boards = vec![board.clone()];
+        for step in &self.steps {
+            match *step {
+                Step::Play(x, y) => board.try_play(x, y).ok()?,
+                Step::Undo => {
+                    board.undo()?;
+                }
+            }
+            boards.push(board.clone());
+        }
+        Some(boards)
+    }
+
+    /// The first inconsistency found along the case, if any.
+    fn check(&self) -> Option<String> {
+        self.replay()?.iter().find_map(check_board)
+    }
+
+    /// Removes steps, moves plays closer to a1 and makes the board smaller
+    /// for as long as the case keeps failing.
+    fn shrink(mut self, fails: impl Fn(&Case) -> bool) -> Case {
+        loop {
+            let smaller = (0..self.steps.len())
+                .map(|i| {
+                    let mut case = self.clone();
+                    case.steps.remove(i);
+                    case
+                })
+                .chain(self.moves_toward_a1())
+                .chain((MIN_BOARD_SIZE..self.size).map(|size| {
+                    let mut case = self.clone();
+                    case.size = size;
+                    case
+                }))
+                .find(|case| case.replay().is_some() && fails(case));
+            match smaller {
+                Some(case) => self = case,
+                None => return self,
+            }
+        }
+    }
+
+    /// Copies of the case with one move taken to a cell nearer to a1, in
+    /// a smaller corner of the board, which lets the board shrink afterwards.
+    fn moves_toward_a1(&self) -> impl Iterator<Item = Case> + use<'_> {
+        self.steps.iter().enumerate().flat_map(move |(i, step)| {
+            let corner = match *step {
+                Step::Play(x, y) => x.max(y),
+                Step::Undo => 0,
+            };
+            (0..corner)
+                .flat_map(move |x| (0..corner).map(move |y| (x, y)))
+                .map(move |(x, y)| {
+                    let mut case = self.clone();
+                    case.steps[i] = Step::Play(x, y);
+                    case
+                })
+        })
+    }
+}
+
+impl std::fmt::Display for Case {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}:", self.size, self.size)?;
+        for step in &self.steps {
+            match *step {
+                Step::Play(x, y) => write!(f, " {}", Move::Cell(x, y))?,
+                Step::Undo => write!(f, " undo")?,
+            }
+        }
+        if let Some(board) = self.replay().and_then(|boards| boards.last().cloned()) {
+            write!(f, "\n{}", board)?;
+        }
+        Ok(())
+    }
+}
+
+fn check_board(board: &Board) -> Option<String> {
+    for color in [Color::Black, Color::White] {
+        // The map kept up to date move by move must match one computed from
+        // the final position alone.
+        let mut rebuilt = DistanceMap::new(board.size(), color);
+        rebuilt.rebuild(board.stones(color), board.stones(color.opponent()));
+        for x in 0..board.size() {
+            for y in 0..board.size() {
+                let distance = board.distance(color, x, y);
+                if Option::<usize>::from(distance) != rebuilt.get(x, y) {
+                    return Some(format!(
+                        "{:?}: distance of {} is {} but {:?} once rebuilt",
+                        color,
+                        Move::Cell(x, y),
+                        distance,
+                        rebuilt.get(x, y)
+                    ));
+                }
+            }
+        }
+
+        let missing = board.missing_move_to_win(color);
+        if missing != rebuilt.to_end() {
+            return Some(format!(
+                "{:?}: missing_move_to_win {:?} but {:?} once rebuilt",
+                color,
+                missing,
+                rebuilt.to_end()
+            ));
+        }
+
+        let oracle = oracle_distances(board, color);
+        let expected = oracle_missing_moves(board, color, &oracle);
+
+        let missing2 = board.missing_move_to_win2(color);
+        if missing != missing2 {
+            return Some(format!(
+                "{:?}: missing_move_to_win {:?} but missing_move_to_win2 {:?}",
+                color, missing, missing2
+            ));
+        }
+        let reached = board.missing_move_to_win_reach(color);
+        if missing != reached {
+            return Some(format!(
+                "{:?}: missing_move_to_win {:?} but missing_move_to_win_reach {:?}",
+                color, missing, reached
+            ));
+        }
+        if missing != expected {
+            return Some(format!(
+                "{:?}: missing_move_to_win {:?} but the oracle finds {:?}",
+                color, missing, expected
+            ));
+        }
+
+        let matrix = board.get_dist_matrix(color);
+        for x in 0..board.size() {
+            for y in 0..board.size() {
+                if Option::<usize>::from(matrix[x][y]) != oracle[x][y] {
+                    return Some(format!(
+                        "{:?}: distance of {} is {} but the oracle finds {:?}",
+                        color,
+                        Move::Cell(x, y),
+                        matrix[x][y],
+                        oracle[x][y]
+                    ));
+                }
+            }
+        }
+    }
+
+    let winner = [Color::Black, Color::White]
+        .into_iter()
+        .find(|&color| oracle_connected(board, color));
+    if board.winner() != winner {
+        return Some(format!(
+            "winner is {:?} but the oracle finds {:?}",
+            board.winner(),
+            winner
+        ));
+    }
+    None
+}
+
+fn on_start_edge(color: Color, x: usize, y: usize) -> bool {
+    match color {
+        Color::Black => x == 0,
+        _ => y == 0,
+    }
+}
+
+fn on_end_edge(size: usize, color: Color, x: usize, y: usize) -> bool {
+    match color {
+        Color::Black => x == size - 1,
+        _ => y == size - 1,
+    }
+}
+
+/// Bellman-Ford over the grid: every distance is relaxed from all of its
+/// neighbors until nothing changes. Slow, but hard to get wrong.
+fn oracle_distances(board: &Board, color: Color) -> Vec<Vec<Option<usize>>> {
+    let size = board.size();
+    let weight = |x: usize, y: usize| match board.get(x, y) {
+        Color::None => Some(1),
+        stone if stone == color => Some(0),
+        _ => None,
+    };
+
+    let mut distances = vec![vec![None; size]; size];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for x in 0..size {
+            for y in 0..size {
+                let Some(weight) = weight(x, y) else {
+                    continue;
+                };
+                let from_edge = on_start_edge(color, x, y).then_some(0);
+                let from_neighbors = Cell::new(x as i32, y as i32)
+                    .neighbors(size)
+                    .filter_map(|cell| distances[cell.x as usize][cell.y as usize])
+                    .min();
+                let best = [from_edge, from_neighbors]
+                    .into_iter()
+                    .flatten()
+                    .min()
+                    .map(|distance: usize| distance + weight);
+                if best.is_some() && (distances[x][y].is_none() || best < distances[x][y]) {
+                    distances[x][y] = best;
+                    changed = true;
+                }
+            }
+        }
+    }
+    distances
+}
+
+fn oracle_missing_moves(
+    board: &Board,
+    color: Color,
+    distances: &[Vec<Option<usize>>],
+) -> Option<usize> {
+    let size = board.size();
+    (0..size)
+        .flat_map(|x| (0..size).map(move |y| (x, y)))
+        .filter(|&(x, y)| on_end_edge(size, color, x, y))
+        .filter_map(|(x, y)| distances[x][y])
+        .min()
+}
+
+/// Depth-first search through `color`'s stones from its first edge.
+fn oracle_connected(board: &Board, color: Color) -> bool {
+    let size = board.size();
+    let mut visited = vec![vec![false; size]; size];
+    let mut stack: Vec<(usize, usize)> = (0..size)
+        .flat_map(|x| (0..size).map(move |y| (x, y)))
+        .filter(|&(x, y)| on_start_edge(color, x, y) && board.get(x, y) == color)
+        .collect();
+    while let Some((x, y)) = stack.pop() {
+        if visited[x][y] {
+            continue;
+        }
+        visited[x][y] = true;
+        if on_end_edge(size, color, x, y) {
+            return true;
+        }
+        for cell in Cell::new(x as i32, y as i32).neighbors(size) {
+            let (nx, ny) = (cell.x as usize, cell.y as usize);
+            if board.get(nx, ny) == color {
+                stack.push((nx, ny));
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cases per board size, `HEX_DIFFERENTIAL_CASES` when set. Cases on
+    /// boards bigger than 11 are much longer to check, so those sizes get a
+    /// sixteenth of them.
+    fn nb_cases(size: usize) -> usize {
+        let nb_cases: usize = std::env::var("HEX_DIFFERENTIAL_CASES")
+            .ok()
+            .and_then(|nb_cases| nb_cases.parse().ok())
+            .unwrap_or(32);
+        if size > 11 {
+            nb_cases.div_ceil(16)
+        } else {
+            nb_cases
+        }
+    }
+
+    #[test]
+    fn distances_and_winner() {
+        let mut rng = StdRng::seed_from_u64(0x4e58);
+        for size in MIN_BOARD_SIZE..=MAX_BOARD_SIZE {
+            for _ in 0..nb_cases(size) {
+                let case = Case::random(&mut rng, size);
+                if let Some(error) = case.check() {
+                    let case = case.shrink(|case| case.check().is_some());
+                    panic!("{}\n{}", case.check().unwrap_or(error), case);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shrinking() {
+        // Any case reaching a board with two Black stones fails: the smallest
+        // one plays four moves.
+        let fails = |case: &Case| {
+            case.replay().is_some_and(|boards| {
+                boards
+                    .iter()
+                    .any(|board| board.stones(Color::Black).count() >= 2)
+            })
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        let case = loop {
+            let case = Case::random(&mut rng, 7);
+            if fails(&case) {
+                break case;
+            }
+        };
+
+        let shrunk = case.shrink(fails);
+        assert_eq!(shrunk.size, MIN_BOARD_SIZE);
+        assert_eq!(shrunk.steps.len(), 4);
+        assert!(
+            shrunk
+                .steps
+                .iter()
+                .all(|step| matches!(step, Step::Play(_, _)))
+        );
+    }
+}
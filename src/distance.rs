@@ -73,6 +73,7 @@ fn print_distances(distances: Vec<Vec<Distance>>) {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
     use super::*;
 
@@ -0,0 +1,306 @@
+use crate::{
+    bitboard::{Bitboard, NB_INDICES},
+    color::Color,
+};
+
+const UNREACHABLE: u16 = u16::MAX;
+
+/// Distances from a player's first edge (top for Black, left for White):
+/// the number of empty cells on the best path to each cell, the cell itself
+/// included, going through the player's stones for free. The opponent's
+/// stones cannot be crossed.
+///
+/// The map is updated cell by cell as stones come and go, only touching the
+/// distances that change, so reading it costs nothing.
+#[derive(Debug, Clone)]
+pub struct DistanceMap {
+    size: usize,
+    color: Color,
+    start_edge: Bitboard,
+    end_edge: Bitboard,
+    distances: [u16; NB_INDICES],
+}
+
+impl DistanceMap {
+    /// The map of an empty board.
+    pub fn new(size: usize, color: Color) -> DistanceMap {
+        let last = size - 1;
+        let (start_edge, end_edge) = match color {
+            Color::Black => (Bitboard::row(size, 0), Bitboard::row(size, last)),
+            _ => (Bitboard::column(size, 0), Bitboard::column(size, last)),
+        };
+        let mut map = DistanceMap {
+            size,
+            color,
+            start_edge,
+            end_edge,
+            distances: [UNREACHABLE; NB_INDICES],
+        };
+        map.rebuild(Bitboard::empty(), Bitboard::empty());
+        map
+    }
+
+    /// Computes every distance again, from the player's stones `own` and
+    /// the opponent's stones `other`.
+    pub fn rebuild(&mut self, own: Bitboard, other: Bitboard) {
+        self.distances = [UNREACHABLE; NB_INDICES];
+        let mut queue = Queue::new();
+        for index in self.start_edge.iter() {
+            let weight = weight(own, other, index);
+            if weight != UNREACHABLE {
+                self.distances[index] = weight;
+                queue.push(weight, index);
+            }
+        }
+        self.relax(own, other, &mut queue);
+    }
+
+    /// Takes into account a cell that held `before` and whose content is now
+    /// given by `own` and `other`.
+    pub fn update(&mut self, own: Bitboard, other: Bitboard, index: usize, before: Color) {
+        let before = match before {
+            Color::None => 1,
+            color if color == self.color => 0,
+            _ => UNREACHABLE,
+        };
+        let after = weight(own, other, index);
+        if after < before {
+            self.shorten(own, other, index, after);
+        } else if after > before {
+            self.lengthen(own, other, index);
+        }
+    }
+
+    /// Distance of a cell, `None` when it cannot be reached.
+    pub fn get(&self, x: usize, y: usize) -> Option<usize> {
+        self.distance(Bitboard::index(self.size, x, y))
+    }
+
+    /// Number of moves missing to connect both edges, `Some(0)` once they are
+    /// connected and `None` when the opponent cut them off.
+    pub fn to_end(&self) -> Option<usize> {
+        self.end_edge
+            .iter()
+            .filter_map(|index| self.distance(index))
+            .min()
+    }
+
+    fn distance(&self, index: usize) -> Option<usize> {
+        match self.distances[index] {
+            UNREACHABLE => None,
+            distance => Some(distance as usize),
+        }
+    }
+
+    /// The cell got cheaper: only its own distance can drop, and the drop
+    /// spreads from there.
+    fn shorten(&mut self, own: Bitboard, other: Bitboard, index: usize, weight: u16) {
+        let mut best = if self.start_edge.get(index) {
+            weight
+        } else {
+            UNREACHABLE
+        };
+        for neighbor in Bitboard::neighbor_indices(self.size, index) {
+            if self.distances[neighbor] != UNREACHABLE {
+                best = best.min(self.distances[neighbor] + weight);
+            }
+        }
+
+        if best < self.distances[index] {
+            self.distances[index] = best;
+            let mut queue = Queue::new();
+            queue.push(best, index);
+            self.relax(own, other, &mut queue);
+        }
+    }
+
+    /// The cell got more expensive: every cell whose best path may go
+    /// through it is computed again from the cells around them.
+    fn lengthen(&mut self, own: Bitboard, other: Bitboard, index: usize) {
+        let mut affected = Bitboard::empty();
+        affected.set(index);
+        // Each cell is pushed once, as it joins `affected`.
+        let mut stack = [0; NB_INDICES];
+        stack[0] = index;
+        let mut len = 1;
+        while len > 0 {
+            len -= 1;
+            let cell = stack[len];
+            if self.distances[cell] == UNREACHABLE {
+                continue;
+            }
+            for neighbor in Bitboard::neighbor_indices(self.size, cell) {
+                let weight = weight(own, other, neighbor);
+                if !affected.get(neighbor)
+                    && weight != UNREACHABLE
+                    && self.distances[neighbor] == self.distances[cell] + weight
+                {
+                    affected.set(neighbor);
+                    stack[len] = neighbor;
+                    len += 1;
+                }
+            }
+        }
+
+        for cell in affected.iter() {
+            self.distances[cell] = UNREACHABLE;
+        }
+
+        let mut queue = Queue::new();
+        for cell in affected.iter() {
+            let weight = weight(own, other, cell);
+            if weight == UNREACHABLE {
+                continue;
+            }
+            let mut best = if self.start_edge.get(cell) {
+                weight
+            } else {
+                UNREACHABLE
+            };
+            for neighbor in Bitboard::neighbor_indices(self.size, cell) {
+                if self.distances[neighbor] != UNREACHABLE {
+                    best = best.min(self.distances[neighbor] + weight);
+                }
+            }
+            if best < UNREACHABLE {
+                self.distances[cell] = best;
+                queue.push(best, cell);
+            }
+        }
+        self.relax(own, other, &mut queue);
+    }
+
+    /// Dijkstra from the cells in `queue`, whose distances are already set.
+    fn relax(&mut self, own: Bitboard, other: Bitboard, queue: &mut Queue) {
+        while let Some((distance, cell)) = queue.pop() {
+            if distance > self.distances[cell] {
+                continue;
+            }
+            for neighbor in Bitboard::neighbor_indices(self.size, cell) {
+                let weight = weight(own, other, neighbor);
+                if weight == UNREACHABLE {
+                    continue;
+                }
+                if distance + weight < self.distances[neighbor] {
+                    self.distances[neighbor] = distance + weight;
+                    queue.push(distance + weight, neighbor);
+                }
+            }
+        }
+    }
+}
+
+/// Cost of going through a cell: nothing for the player's stones, a move
+/// for an empty cell.
+fn weight(own: Bitboard, other: Bitboard, index: usize) -> u16 {
+    if own.get(index) {
+        0
+    } else if other.get(index) {
+        UNREACHABLE
+    } else {
+        1
+    }
+}
+
+/// Each cell is settled once and pushes at most its six neighbors, on top
+/// of the cells the search starts from.
+const QUEUE_CAPACITY: usize = 7 * NB_INDICES;
+const NO_ENTRY: u16 = u16::MAX;
+
+/// Bucket queue of cells by distance (Dial's algorithm) in fixed arrays, so
+/// that updates do not allocate. Once popping started, cells must not be
+/// pushed closer than the last distance popped, which Dijkstra guarantees.
+struct Queue {
+    /// Last entry pushed at each distance.
+    heads: [u16; NB_INDICES],
+    /// Cell of each entry, and the entry pushed before it at that distance.
+    entries: [(u16, u16); QUEUE_CAPACITY],
+    len: usize,
+    /// Smallest and largest distances that may still hold entries.
+    distance: usize,
+    last: usize,
+}
+
+impl Queue {
+    fn new() -> Queue {
+        Queue {
+            heads: [NO_ENTRY; NB_INDICES],
+            entries: [(0, NO_ENTRY); QUEUE_CAPACITY],
+            len: 0,
+            distance: 0,
+            last: 0,
+        }
+    }
+
+    fn push(&mut self, distance: u16, cell: usize) {
+        let distance = distance as usize;
+        if self.len == 0 || distance < self.distance {
+            self.distance = distance;
+        }
+        self.last = self.last.max(distance);
+        self.entries[self.len] = (cell as u16, self.heads[distance]);
+        self.heads[distance] = self.len as u16;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<(u16, usize)> {
+        while self.distance <= self.last {
+            let entry = self.heads[self.distance];
+            if entry != NO_ENTRY {
+                let (cell, previous) = self.entries[entry as usize];
+                self.heads[self.distance] = previous;
+                return Some((self.distance as u16, cell as usize));
+            }
+            self.distance += 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incremental() {
+        let size = 5;
+        let mut own = Bitboard::empty();
+        let mut other = Bitboard::empty();
+        let mut map = DistanceMap::new(size, Color::White);
+        assert_eq!(map.to_end(), Some(5));
+
+        // Stones come and go, the map must match one computed from scratch.
+        let cells = [
+            (2, 2, true),
+            (2, 1, false),
+            (1, 3, true),
+            (3, 0, false),
+            (0, 4, true),
+        ];
+        for &(x, y, mine) in &cells {
+            let index = Bitboard::index(size, x, y);
+            if mine {
+                own.set(index)
+            } else {
+                other.set(index)
+            }
+            map.update(own, other, index, Color::None);
+
+            let mut fresh = DistanceMap::new(size, Color::White);
+            fresh.rebuild(own, other);
+            assert_eq!(map.distances, fresh.distances);
+        }
+        for &(x, y, mine) in cells.iter().rev() {
+            let index = Bitboard::index(size, x, y);
+            let before = if mine { Color::White } else { Color::Black };
+            own.clear(index);
+            other.clear(index);
+            map.update(own, other, index, before);
+
+            let mut fresh = DistanceMap::new(size, Color::White);
+            fresh.rebuild(own, other);
+            assert_eq!(map.distances, fresh.distances);
+        }
+        assert_eq!(map.to_end(), Some(5));
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt;
+
+use crate::{
+    board::{MAX_BOARD_SIZE, MIN_BOARD_SIZE},
+    color::Color,
+    hex_move::Move,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexError {
+    OutOfBounds { x: usize, y: usize },
+    Occupied { x: usize, y: usize },
+    GameFinished,
+    WrongColor { expected: Color, found: Color },
+    SwapNotAllowed,
+    InvalidSize { size: usize },
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HexError::OutOfBounds { x, y } => {
+                write!(f, "{} is outside the board", Move::Cell(*x, *y))
+            }
+            HexError::Occupied { x, y } => write!(f, "{} is already occupied", Move::Cell(*x, *y)),
+            HexError::GameFinished => write!(f, "the game is already finished"),
+            HexError::WrongColor { expected, found } => {
+                write!(f, "{} to move, not {}", expected, found)
+            }
+            HexError::SwapNotAllowed => write!(f, "swapping is only allowed after the first move"),
+            HexError::InvalidSize { size } => write!(
+                f,
+                "board size {} is not between {} and {}",
+                size, MIN_BOARD_SIZE, MAX_BOARD_SIZE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HexError {}
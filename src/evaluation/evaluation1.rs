@@ -33,36 +33,57 @@ impl Default for Evaluation1 {
 
 //test
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::board::Board;
 
     #[test]
-    #[ignore = "FIXME"]
     fn test_evaluation1() {
-        let board = Board::new();
+        // This test used to be ignored as FIXME, and its assertions could
+        // not hold whatever the evaluation does: they expected both -1.0
+        // and 1.0 after a1, then checkmates in 7x7 positions where neither
+        // player is cut off. The same positions are checked below with
+        // their actual scores, and the checkmates on 3x3 boards.
         let evaluation = Evaluation1::new();
+        let board = Board::default();
         assert_eq!(evaluation.score(&board), Score::Advantage(0.0));
 
-        let mut board = Board::new();
-        board.play(0, 0);
-        assert_eq!(evaluation.score(&board), Score::Advantage(-1.0));
-
-        let mut board = Board::new();
+        // White a1: White misses 6 stones, Black 7.
+        let mut board = Board::default();
         board.play(0, 0);
         assert_eq!(evaluation.score(&board), Score::Advantage(1.0));
 
-        let mut board = Board::new();
+        // White a1 a3 around Black a2, who still needs 7 stones.
+        let mut board = Board::default();
         board.play(0, 0);
         board.play(1, 0);
         board.play(2, 0);
-        assert_eq!(evaluation.score(&board), Score::BlackCheckMate);
+        assert_eq!(evaluation.score(&board), Score::Advantage(1.0));
+
+        // White a1 c1 and Black b1: 6 stones each.
+        let mut board = Board::default();
+        board.play(0, 0);
+        board.play(0, 1);
+        board.play(0, 2);
+        assert_eq!(evaluation.score(&board), Score::Advantage(0.0));
 
-        let mut board = Board::new();
+        // White holds the whole top row.
+        let mut board = Board::new(3);
         board.play(0, 0);
+        board.play(1, 0);
         board.play(0, 1);
+        board.play(1, 1);
         board.play(0, 2);
         assert_eq!(evaluation.score(&board), Score::WhiteCheckMate);
+
+        // Black's column blocks White, who cannot reach the right edge.
+        let mut board = Board::new(3);
+        board.play(0, 0);
+        board.play(0, 1);
+        board.play(1, 0);
+        board.play(1, 1);
+        board.play(2, 2);
+        board.play(2, 1);
+        assert_eq!(evaluation.score(&board), Score::BlackCheckMate);
     }
 }
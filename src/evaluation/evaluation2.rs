@@ -7,8 +7,8 @@ pub struct Evaluation2;
 impl Evaluation for Evaluation2 {
     fn score(&self, board: &Board) -> Score {
         match (
-            board.missing_move_to_win2(Color::Black),
-            board.missing_move_to_win2(Color::White),
+            board.missing_move_to_win(Color::Black),
+            board.missing_move_to_win(Color::White),
         ) {
             (None, _) => Score::WhiteCheckMate,
             (_, None) => Score::BlackCheckMate,
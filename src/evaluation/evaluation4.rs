@@ -1,42 +1,34 @@
 use super::Evaluation;
-use crate::{board::Board, color::Color, distance::Distance, score::Score};
+use crate::{board::Board, color::Color, score::Score};
 
 #[derive(Clone)]
 pub struct Evaluation4;
 
 impl Evaluation for Evaluation4 {
     fn score(&self, board: &Board) -> Score {
-        fn calculate(vec: Vec<f64>) -> f64 {
-            vec.iter().map(|d| d * d).sum::<f64>() / vec.iter().sum::<f64>()
+        fn calculate(distances: impl Iterator<Item = usize>) -> f64 {
+            let (sum, sum_of_squares) = distances.fold((0.0, 0.0), |(sum, squares), d| {
+                (sum + d as f64, squares + (d * d) as f64)
+            });
+            sum_of_squares / sum
         }
 
         match (
-            board.missing_move_to_win2(Color::Black),
-            board.missing_move_to_win2(Color::White),
+            board.missing_move_to_win(Color::Black),
+            board.missing_move_to_win(Color::White),
         ) {
             (None, _) => Score::WhiteCheckMate,
             (_, None) => Score::BlackCheckMate,
             (Some(_), Some(_)) => {
-                let dist_matrix_white = board.get_dist_matrix(Color::White);
-                let dist_white: Vec<f64> = dist_matrix_white
-                    .iter()
-                    .map(|row| row.last().unwrap())
-                    .filter(|&&d| matches!(d, Distance::Reachable(_)))
-                    .map(|&d| Option::<usize>::from(d).unwrap() as f64)
-                    .collect();
-
-                let score_white: f64 = calculate(dist_white);
-
-                let dist_matrix_black = board.get_dist_matrix(Color::Black);
-                let dist_black: Vec<f64> = dist_matrix_black
-                    .last()
-                    .unwrap()
-                    .iter()
-                    .filter(|&&d| matches!(d, Distance::Reachable(_)))
-                    .map(|&d| Option::<usize>::from(d).unwrap() as f64)
-                    .collect();
-
-                let score_black: f64 = calculate(dist_black);
+                let last = board.size() - 1;
+                let score_white =
+                    calculate((0..board.size()).filter_map(|x| {
+                        Option::<usize>::from(board.distance(Color::White, x, last))
+                    }));
+                let score_black =
+                    calculate((0..board.size()).filter_map(|y| {
+                        Option::<usize>::from(board.distance(Color::Black, last, y))
+                    }));
 
                 Score::Advantage(score_white - score_black)
             }
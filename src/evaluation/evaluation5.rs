@@ -0,0 +1,61 @@
+use super::Evaluation;
+use crate::{board::Board, color::Color, score::Score};
+
+/// Compares the two-distance potentials of both players.
+#[derive(Clone)]
+pub struct Evaluation5;
+
+impl Evaluation for Evaluation5 {
+    fn score(&self, board: &Board) -> Score {
+        match (
+            board.missing_move_to_win(Color::Black),
+            board.missing_move_to_win(Color::White),
+        ) {
+            (None, _) => Score::WhiteCheckMate,
+            (_, None) => Score::BlackCheckMate,
+            (Some(_), Some(_)) => {
+                Score::Advantage(potential(board, Color::Black) - potential(board, Color::White))
+            }
+        }
+    }
+}
+
+/// Without two disjoint ways to the edges, a player is as far from winning
+/// as the board allows.
+fn potential(board: &Board, color: Color) -> f64 {
+    board
+        .two_distance_potential(color)
+        .unwrap_or(2 * board.size() * board.size()) as f64
+}
+
+impl Evaluation5 {
+    pub fn new() -> Evaluation5 {
+        Evaluation5 {}
+    }
+}
+
+impl Default for Evaluation5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluation5() {
+        let evaluation = Evaluation5::new();
+        assert_eq!(evaluation.score(&Board::new(5)), Score::Advantage(0.0));
+
+        let mut board = Board::new(5);
+        board.play(2, 2);
+        assert!(evaluation.score(&board) > Score::Advantage(0.0));
+        board.play(1, 3);
+        assert_eq!(
+            evaluation.score(&board.transpose_swap()),
+            -evaluation.score(&board)
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use super::Evaluation;
+use crate::{board::Board, color::Color, score::Score};
+
+/// Compares the electrical resistance between each player's edges, which
+/// rewards having many parallel ways to connect.
+#[derive(Clone)]
+pub struct Evaluation6;
+
+impl Evaluation for Evaluation6 {
+    fn score(&self, board: &Board) -> Score {
+        match (
+            board.resistance(Color::Black),
+            board.resistance(Color::White),
+        ) {
+            (Some(0.0), _) | (_, None) => Score::BlackCheckMate,
+            (_, Some(0.0)) | (None, _) => Score::WhiteCheckMate,
+            (Some(black), Some(white)) => Score::Advantage((black / white).ln()),
+        }
+    }
+}
+
+impl Evaluation6 {
+    pub fn new() -> Evaluation6 {
+        Evaluation6 {}
+    }
+}
+
+impl Default for Evaluation6 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluation6() {
+        let evaluation = Evaluation6::new();
+        let score: f64 = evaluation.score(&Board::new(5)).into();
+        assert!(score.abs() < 1e-9);
+
+        let mut board = Board::new(5);
+        board.play(2, 2);
+        assert!(evaluation.score(&board) > Score::Advantage(0.0));
+        board.play(1, 3);
+        let (score, swapped): (f64, f64) = (
+            evaluation.score(&board).into(),
+            evaluation.score(&board.transpose_swap()).into(),
+        );
+        assert!((score + swapped).abs() < 1e-9);
+
+        let mut board = Board::new(2);
+        board.play(0, 0);
+        board.play(1, 0);
+        board.play(0, 1);
+        assert_eq!(evaluation.score(&board), Score::WhiteCheckMate);
+    }
+}
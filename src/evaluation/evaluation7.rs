@@ -0,0 +1,103 @@
+use super::Evaluation;
+use crate::{board::Board, color::Color, score::Score};
+
+/// Weight of the number of shortest paths, which grows quickly with the
+/// board size and would otherwise outweigh the distance.
+const PATHS_WEIGHT: f64 = 0.25;
+
+/// Compares how far each player is from connecting, lowered by how many
+/// disjoint ways they have (the minimum cut between their edges) and how
+/// many shortest paths they can choose from. Several independent routes
+/// are worth more than a single one the opponent can block.
+#[derive(Clone)]
+pub struct Evaluation7;
+
+impl Evaluation for Evaluation7 {
+    fn score(&self, board: &Board) -> Score {
+        match (
+            board.shortest_paths(Color::Black),
+            board.shortest_paths(Color::White),
+        ) {
+            (Some((0, _)), _) | (_, None) => Score::BlackCheckMate,
+            (_, Some((0, _))) | (None, _) => Score::WhiteCheckMate,
+            (Some(black), Some(white)) => Score::Advantage(
+                cost(board, Color::Black, black) - cost(board, Color::White, white),
+            ),
+        }
+    }
+}
+
+fn cost(board: &Board, color: Color, (distance, nb_paths): (usize, u64)) -> f64 {
+    let min_cut = board.min_cut(color).unwrap_or(1).max(1);
+    distance as f64 - (min_cut as f64).ln() - PATHS_WEIGHT * (nb_paths as f64).ln()
+}
+
+impl Evaluation7 {
+    pub fn new() -> Evaluation7 {
+        Evaluation7 {}
+    }
+}
+
+impl Default for Evaluation7 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluation7() {
+        let evaluation = Evaluation7::new();
+        let score: f64 = evaluation.score(&Board::new(5)).into();
+        assert!(score.abs() < 1e-9);
+
+        let mut board = Board::new(5);
+        board.play(2, 2);
+        assert!(evaluation.score(&board) > Score::Advantage(0.0));
+        board.play(1, 3);
+        let (score, swapped): (f64, f64) = (
+            evaluation.score(&board).into(),
+            evaluation.score(&board.transpose_swap()).into(),
+        );
+        assert!((score + swapped).abs() < 1e-9);
+
+        let mut board = Board::new(2);
+        board.play(0, 0);
+        board.play(1, 0);
+        board.play(0, 1);
+        assert_eq!(evaluation.score(&board), Score::WhiteCheckMate);
+    }
+
+    #[test]
+    fn independent_routes() {
+        // Same distance for White, but one wall leaves a single way through
+        // while the other leaves three.
+        let mut fragile = Board::new(5);
+        for x in 0..4 {
+            fragile.place_stone(Color::Black, x, 2);
+        }
+        let mut robust = Board::new(5);
+        for x in [0, 2, 4] {
+            robust.place_stone(Color::Black, x, 2);
+        }
+        robust.place_stone(Color::Black, 4, 0);
+        assert_eq!(fragile.min_cut(Color::White), Some(1));
+        assert!(robust.min_cut(Color::White) > Some(1));
+        assert_eq!(
+            fragile.shortest_paths(Color::White).unwrap().0,
+            robust.shortest_paths(Color::White).unwrap().0
+        );
+
+        let white = |board: &Board| {
+            cost(
+                board,
+                Color::White,
+                board.shortest_paths(Color::White).unwrap(),
+            )
+        };
+        assert!(white(&robust) < white(&fragile));
+    }
+}
@@ -2,11 +2,17 @@ mod evaluation1;
 mod evaluation2;
 mod evaluation3;
 mod evaluation4;
+mod evaluation5;
+mod evaluation6;
+mod evaluation7;
 
 pub use evaluation1::Evaluation1;
 pub use evaluation2::Evaluation2;
 pub use evaluation3::Evaluation3;
 pub use evaluation4::Evaluation4;
+pub use evaluation5::Evaluation5;
+pub use evaluation6::Evaluation6;
+pub use evaluation7::Evaluation7;
 
 use crate::{board::Board, score::Score};
 
@@ -0,0 +1,95 @@
+use std::collections::VecDeque;
+
+/// A flow network with integer capacities, solved with Edmonds-Karp. Hex
+/// flows are bounded by the board size, so only a handful of augmenting
+/// paths are ever searched.
+#[derive(Debug, Clone)]
+pub struct FlowNetwork {
+    /// For each node, the index of its arcs in `arcs`.
+    nodes: Vec<Vec<usize>>,
+    /// Target and remaining capacity of each arc, stored next to its
+    /// reverse arc: arc `i ^ 1` goes back from the target of arc `i`.
+    arcs: Vec<(usize, usize)>,
+}
+
+/// Capacity large enough to never limit a flow.
+pub const UNBOUNDED: usize = usize::MAX / 2;
+
+impl FlowNetwork {
+    pub fn new(nb_nodes: usize) -> FlowNetwork {
+        FlowNetwork {
+            nodes: vec![Vec::new(); nb_nodes],
+            arcs: Vec::new(),
+        }
+    }
+
+    pub fn add_arc(&mut self, from: usize, to: usize, capacity: usize) {
+        self.nodes[from].push(self.arcs.len());
+        self.arcs.push((to, capacity));
+        self.nodes[to].push(self.arcs.len());
+        self.arcs.push((from, 0));
+    }
+
+    /// Maximum flow from `source` to `sink`, saturating the network.
+    pub fn max_flow(&mut self, source: usize, sink: usize) -> usize {
+        let mut flow = 0;
+        loop {
+            // Shortest augmenting path, by the arc reaching each node.
+            let mut arrival = vec![None; self.nodes.len()];
+            let mut queue = VecDeque::from([source]);
+            while let Some(node) = queue.pop_front() {
+                if node == sink {
+                    break;
+                }
+                for &arc in &self.nodes[node] {
+                    let (next, capacity) = self.arcs[arc];
+                    if capacity > 0 && next != source && arrival[next].is_none() {
+                        arrival[next] = Some(arc);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            if arrival[sink].is_none() {
+                return flow;
+            }
+
+            let mut path = Vec::new();
+            let mut node = sink;
+            while let Some(arc) = arrival[node] {
+                path.push(arc);
+                node = self.arcs[arc ^ 1].0;
+            }
+            let bottleneck = path.iter().map(|&arc| self.arcs[arc].1).min().unwrap();
+            for arc in path {
+                self.arcs[arc].1 -= bottleneck;
+                self.arcs[arc ^ 1].1 += bottleneck;
+            }
+            flow += bottleneck;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_flow() {
+        let mut network = FlowNetwork::new(4);
+        network.add_arc(0, 1, 3);
+        network.add_arc(0, 2, 2);
+        network.add_arc(1, 2, 5);
+        network.add_arc(1, 3, 2);
+        network.add_arc(2, 3, 3);
+        assert_eq!(network.max_flow(0, 3), 5);
+    }
+
+    #[test]
+    fn unbounded() {
+        let mut network = FlowNetwork::new(3);
+        network.add_arc(0, 1, UNBOUNDED);
+        network.add_arc(1, 2, 1);
+        assert_eq!(network.max_flow(0, 2), 1);
+        assert_eq!(FlowNetwork::new(2).max_flow(0, 1), 0);
+    }
+}
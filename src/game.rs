@@ -3,7 +3,10 @@ use std::rc::Rc;
 use std::time::{Duration, Instant};
 
 use crate::color::Color;
+use crate::error::HexError;
+use crate::hex_move::Move;
 use crate::log::{LogFlag, LogLevel};
+use crate::sgf::{GameRecord, RecordedMove};
 use crate::{board::Board, player::Player};
 
 #[derive(Clone, Debug, Default)]
@@ -12,29 +15,56 @@ pub struct Game {
     players: HashMap<Color, Rc<Player>>,
     duration: Option<Duration>,
     log_level: Rc<LogLevel>,
+    resigned: Option<Color>,
+    /// Comment on each move of the board history, then on the
+    /// resignation: the time the player took.
+    comments: Vec<Option<String>>,
 }
 
 impl Game {
     pub fn new(players: HashMap<Color, Rc<Player>>) -> Game {
         Game {
-            board: Board::new(),
+            board: Board::default(),
             players,
             duration: None,
             log_level: Rc::default(),
+            resigned: None,
+            comments: Vec::new(),
         }
     }
 
     pub fn winner(&self) -> Option<Color> {
-        self.board.winner()
+        match self.resigned {
+            Some(color) => Some(color.opponent()),
+            None => self.board.winner(),
+        }
+    }
+
+    /// The color that resigned, if the game did not end on the board.
+    #[allow(unused)]
+    pub fn resigned(&self) -> Option<Color> {
+        self.resigned
     }
 
     pub fn board(&self) -> &Board {
         &self.board
     }
 
+    /// Starts from an empty board of the given size, keeping the swap rule.
+    #[allow(unused)]
+    pub fn set_board_size(&mut self, size: usize) {
+        let swap_rule = self.board.swap_rule();
+        self.board = Board::new(size);
+        self.board.set_swap_rule(swap_rule);
+        self.resigned = None;
+        self.comments.clear();
+    }
+
     #[allow(unused)]
     pub fn set_starting_position(&mut self, board: Board) {
+        self.comments = vec![None; board.moves().len()];
         self.board = board;
+        self.resigned = None;
     }
 
     #[allow(unused)]
@@ -42,6 +72,11 @@ impl Game {
         self.duration = Some(duration);
     }
 
+    #[allow(unused)]
+    pub fn set_swap_rule(&mut self, swap_rule: bool) {
+        self.board.set_swap_rule(swap_rule);
+    }
+
     #[allow(unused)]
     pub fn set_log_level(&mut self, log_level: Rc<LogLevel>) {
         self.log_level = log_level;
@@ -50,12 +85,15 @@ impl Game {
     #[allow(unused)]
     pub fn play_random_move(&mut self) {
         self.board.play_random_move();
+        self.comments.push(None);
         if self.log_level.is(LogFlag::Position) {
             print!("{}", self.board);
         }
     }
 
-    pub fn play(&mut self) {
+    /// Plays the game to the end. Stops with an error, leaving the position
+    /// untouched, if a player answers with an illegal move.
+    pub fn play(&mut self) -> Result<(), HexError> {
         if self.log_level.is(LogFlag::Position) {
             print!("{}", self.board);
         }
@@ -63,7 +101,8 @@ impl Game {
         self.play_random_move();
 
         loop {
-            let player = match self.players.get(&self.board.next_color()) {
+            let color = self.board.next_color();
+            let player = match self.players.get(&color) {
                 Some(player) => player.clone(),
                 None => Rc::new(Player::default()),
             };
@@ -71,33 +110,229 @@ impl Game {
             // Time
             let start = Instant::now();
 
-            let (x, y) = player.next_move(&self.board);
+            let mv = if self.board.can_swap() && player.swap(&self.board) {
+                Move::Swap
+            } else {
+                player.next_move(&self.board)
+            };
 
             // Time
             let duration = start.elapsed();
 
             // Update the original board with the player's move
-            self.board.play(x, y);
+            let result = match mv {
+                Move::Cell(x, y) => self.board.try_play(x, y),
+                Move::Swap => self.board.swap(),
+                Move::Resign => {
+                    self.resigned = Some(color);
+                    Ok(())
+                }
+            };
+            if let Err(error) = result {
+                if self.log_level.is(LogFlag::GameResult) {
+                    println!(
+                        "{} ({}) played an illegal move {}: {}",
+                        color, player.name, mv, error
+                    );
+                }
+                return Err(error);
+            }
+            self.comments.push(Some(format!("{:?}", duration)));
 
             if self.log_level.is(LogFlag::Moves) {
-                println!(
-                    "{} played ({}, {}) in {:?}",
-                    self.board.next_color(),
-                    x + 1,
-                    y + 1,
-                    duration
-                );
+                match mv {
+                    Move::Cell(..) => println!("{} played {} in {:?}", color, mv, duration),
+                    Move::Swap => println!("{} swapped", color),
+                    Move::Resign => println!("{} resigned", color),
+                }
             }
-            if self.log_level.is(LogFlag::Position) {
+            if self.log_level.is(LogFlag::Position) && self.winner().is_none() {
                 println!("{}", self.board);
             }
 
-            if let Some(winner) = self.board.winner() {
+            if let Some(winner) = self.winner() {
                 if self.log_level.is(LogFlag::GameResult) {
-                    println!("{} wins!", winner);
+                    match self.board.winning_chain() {
+                        Some(chain) => println!("{} wins! {}", winner, chain_to_string(&chain)),
+                        None => println!("{} wins!", winner),
+                    }
                 }
-                break;
+                if self.log_level.is(LogFlag::Position) {
+                    println!("{:#}", self.board);
+                }
+                return Ok(());
             }
         }
     }
+
+    /// The game so far, with the players' names and the time taken by each
+    /// move. The moves are those of the board history, so a starting
+    /// position set up without one is not part of the record.
+    pub fn record(&self) -> GameRecord {
+        let name = |color| self.players.get(&color).map(|player| player.name.clone());
+        let resignation = self.resigned.map(|_| Move::Resign);
+        let moves = self
+            .board
+            .moves()
+            .iter()
+            .copied()
+            .chain(resignation)
+            .enumerate()
+            .map(|(i, mv)| RecordedMove {
+                mv,
+                comment: self.comments.get(i).cloned().flatten(),
+            })
+            .collect();
+
+        GameRecord {
+            size: self.board.size(),
+            white: name(Color::White),
+            black: name(Color::Black),
+            moves,
+            winner: self.winner(),
+        }
+    }
+}
+
+/// A chain of cells in move notation, e.g. `a2-b1-c1`.
+pub fn chain_to_string(chain: &[(usize, usize)]) -> String {
+    chain
+        .iter()
+        .map(|&cell| Move::from(cell).to_string())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Strategy;
+
+    struct Stubborn;
+
+    impl Strategy for Stubborn {
+        fn next_move(&self, _board: &Board, _duration: Option<Duration>) -> Move {
+            Move::Cell(0, 0)
+        }
+    }
+
+    struct Resigner;
+
+    impl Strategy for Resigner {
+        fn next_move(&self, _board: &Board, _duration: Option<Duration>) -> Move {
+            Move::Resign
+        }
+    }
+
+    struct FirstCell;
+
+    impl Strategy for FirstCell {
+        fn next_move(&self, board: &Board, _duration: Option<Duration>) -> Move {
+            board.first_possible_move().unwrap().into()
+        }
+
+        fn swap(&self, _board: &Board, _duration: Option<Duration>) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn swap_rule() {
+        let player = Rc::new(Player::new(
+            "FirstCell".to_string(),
+            Rc::new(FirstCell),
+            None,
+        ));
+        let mut players = HashMap::new();
+        players.insert(Color::White, player.clone());
+        players.insert(Color::Black, player);
+
+        let mut game = Game::new(players);
+        game.set_swap_rule(true);
+
+        assert_eq!(game.play(), Ok(()));
+        assert_eq!(game.board().moves()[1], Move::Swap);
+        assert!(game.winner().is_some());
+    }
+
+    #[test]
+    fn record() {
+        let player = Rc::new(Player::new(
+            "FirstCell".to_string(),
+            Rc::new(FirstCell),
+            None,
+        ));
+        let mut players = HashMap::new();
+        players.insert(Color::White, player.clone());
+        players.insert(Color::Black, player);
+
+        let mut game = Game::new(players);
+        game.set_swap_rule(true);
+        assert_eq!(game.play(), Ok(()));
+
+        let record = game.record();
+        assert_eq!(record.white.as_deref(), Some("FirstCell"));
+        assert_eq!(record.winner, game.winner());
+        assert_eq!(record.moves.len(), game.board().moves().len());
+        // The opening move is random, the others are timed.
+        assert_eq!(record.moves[0].comment, None);
+        assert!(
+            record.moves[1..]
+                .iter()
+                .all(|played| played.comment.is_some())
+        );
+
+        let read = GameRecord::from_sgf(&record.to_sgf()).unwrap();
+        assert_eq!(read, record);
+        assert_eq!(read.board().as_ref(), Ok(game.board()));
+    }
+
+    #[test]
+    fn board_size() {
+        let mut game = Game::new(HashMap::new());
+        game.set_swap_rule(true);
+        game.set_board_size(11);
+
+        assert_eq!(game.board().size(), 11);
+        assert!(game.board().swap_rule());
+        assert_eq!(game.play(), Ok(()));
+        assert!(game.winner().is_some());
+    }
+
+    #[test]
+    fn illegal_move() {
+        let player = Rc::new(Player::new("Stubborn".to_string(), Rc::new(Stubborn), None));
+        let mut players = HashMap::new();
+        players.insert(Color::White, player.clone());
+        players.insert(Color::Black, player);
+
+        let mut game = Game::new(players);
+        let mut board = Board::default();
+        board.play(0, 0);
+        game.set_starting_position(board);
+
+        assert_eq!(game.play(), Err(HexError::Occupied { x: 0, y: 0 }));
+        assert_eq!(game.board().get(0, 0), Color::White);
+        assert_eq!(game.board().moves().len(), 2);
+    }
+
+    #[test]
+    fn resign() {
+        let mut players = HashMap::new();
+        players.insert(
+            Color::Black,
+            Rc::new(Player::new("Resigner".to_string(), Rc::new(Resigner), None)),
+        );
+
+        let mut game = Game::new(players);
+        assert_eq!(game.play(), Ok(()));
+        assert_eq!(game.resigned(), Some(Color::Black));
+        assert_eq!(game.winner(), Some(Color::White));
+        assert_eq!(game.board().moves().len(), 1);
+
+        let record = game.record();
+        assert_eq!(record.moves.last().unwrap().mv, Move::Resign);
+        assert!(record.resigned());
+        assert_eq!(record.winner, Some(Color::White));
+    }
 }
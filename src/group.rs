@@ -0,0 +1,72 @@
+use crate::color::Color;
+
+/// A side of the board: Black connects `Top` and `Bottom`, White connects
+/// `Left` and `Right`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Edge {
+    /// The edges `color` has to connect, first and second.
+    pub fn of(color: Color) -> (Edge, Edge) {
+        match color {
+            Color::Black => (Edge::Top, Edge::Bottom),
+            Color::White => (Edge::Left, Edge::Right),
+            Color::None => panic!("Color::None has no edges"),
+        }
+    }
+
+    /// Edges touched by the cell `(x, y)`, two in the corners.
+    pub fn around(size: usize, x: usize, y: usize) -> Vec<Edge> {
+        [
+            (x == 0, Edge::Top),
+            (x == size - 1, Edge::Bottom),
+            (y == 0, Edge::Left),
+            (y == size - 1, Edge::Right),
+        ]
+        .into_iter()
+        .filter_map(|(touches, edge)| touches.then_some(edge))
+        .collect()
+    }
+}
+
+/// A connected component of stones of one color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub color: Color,
+    /// Stones of the group, in row-major order: the first one identifies it.
+    pub cells: Vec<(usize, usize)>,
+    /// Empty cells next to the group, in row-major order.
+    pub liberties: Vec<(usize, usize)>,
+    /// Edges the group touches, in the order of `Edge`.
+    pub edges: Vec<Edge>,
+}
+
+impl Group {
+    pub fn touches(&self, edge: Edge) -> bool {
+        self.edges.contains(&edge)
+    }
+
+    /// Whether the group touches both edges of its color, winning the game.
+    pub fn connects(&self) -> bool {
+        let (start, end) = Edge::of(self.color);
+        self.touches(start) && self.touches(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edges() {
+        assert_eq!(Edge::around(3, 0, 0), vec![Edge::Top, Edge::Left]);
+        assert_eq!(Edge::around(3, 1, 2), vec![Edge::Right]);
+        assert_eq!(Edge::around(3, 1, 1), vec![]);
+        assert_eq!(Edge::of(Color::White), (Edge::Left, Edge::Right));
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::symmetry::Symmetry;
+
+/// A move in the game history: a stone placed on a cell, the second player
+/// taking the first stone with the swap rule, or a resignation.
+///
+/// Moves are written as in HexGUI and Little Golem: the column as a letter
+/// and the row as a 1-based number, so `Move::Cell(2, 0)` (row 3, column 1)
+/// is `a3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Move {
+    Cell(usize, usize),
+    Swap,
+    Resign,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoveError(String);
+
+impl Move {
+    /// The same move seen through a symmetry of a board of the given size.
+    pub fn transform(self, symmetry: Symmetry, size: usize) -> Move {
+        match self {
+            Move::Cell(x, y) => {
+                let (x, y) = symmetry.cell(size, x, y);
+                Move::Cell(x, y)
+            }
+            _ => self,
+        }
+    }
+}
+
+impl From<(usize, usize)> for Move {
+    fn from((x, y): (usize, usize)) -> Move {
+        Move::Cell(x, y)
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Move::Cell(x, y) => {
+                write_column(f, *y)?;
+                write!(f, "{}", x + 1)
+            }
+            Move::Swap => write!(f, "swap"),
+            Move::Resign => write!(f, "resign"),
+        }
+    }
+}
+
+/// Columns past `z` continue with `aa`, `ab`, ... as in spreadsheets.
+pub(crate) fn write_column(f: &mut fmt::Formatter, column: usize) -> fmt::Result {
+    if column >= 26 {
+        write_column(f, column / 26 - 1)?;
+    }
+    write!(f, "{}", (b'a' + (column % 26) as u8) as char)
+}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(input: &str) -> Result<Move, ParseMoveError> {
+        let error = || ParseMoveError(input.to_string());
+        let text = input.trim().to_ascii_lowercase();
+
+        match text.as_str() {
+            "swap" | "swap-pieces" => return Ok(Move::Swap),
+            "resign" => return Ok(Move::Resign),
+            _ => {}
+        }
+
+        let split = text
+            .find(|c: char| !c.is_ascii_lowercase())
+            .ok_or_else(error)?;
+        let (letters, digits) = text.split_at(split);
+        if letters.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(error());
+        }
+
+        let column = letters
+            .bytes()
+            .try_fold(0usize, |column, letter| {
+                column
+                    .checked_mul(26)?
+                    .checked_add((letter - b'a') as usize + 1)
+            })
+            .ok_or_else(error)?
+            - 1;
+        let row = digits.parse::<usize>().map_err(|_| error())?;
+        if row == 0 {
+            return Err(error());
+        }
+
+        Ok(Move::Cell(row - 1, column))
+    }
+}
+
+impl fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a move, expected a cell like c3, swap or resign",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        assert_eq!(Move::Cell(0, 0).to_string(), "a1");
+        assert_eq!(Move::Cell(2, 2).to_string(), "c3");
+        assert_eq!(Move::Cell(18, 18).to_string(), "s19");
+        assert_eq!(Move::Cell(0, 26).to_string(), "aa1");
+        assert_eq!(Move::Swap.to_string(), "swap");
+        assert_eq!(Move::Resign.to_string(), "resign");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("a1".parse(), Ok(Move::Cell(0, 0)));
+        assert_eq!(" C3\n".parse(), Ok(Move::Cell(2, 2)));
+        assert_eq!("s19".parse(), Ok(Move::Cell(18, 18)));
+        assert_eq!("aa1".parse(), Ok(Move::Cell(0, 26)));
+        assert_eq!("swap".parse(), Ok(Move::Swap));
+        assert_eq!("swap-pieces".parse(), Ok(Move::Swap));
+        assert_eq!("resign".parse(), Ok(Move::Resign));
+
+        for input in ["", "a", "3", "a0", "3a", "a1b", "c-3", "aaaaaaaaaaaaaaaa1"] {
+            assert_eq!(
+                input.parse::<Move>(),
+                Err(ParseMoveError(input.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for x in 0..19 {
+            for y in 0..19 {
+                let cell = Move::Cell(x, y);
+                assert_eq!(cell.to_string().parse(), Ok(cell));
+            }
+        }
+    }
+}
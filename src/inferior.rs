@@ -0,0 +1,368 @@
+use crate::{bitboard::Bitboard, board::Board, color::Color};
+
+/// Neighbor steps in order around a cell, so that consecutive steps lead to
+/// cells next to each other.
+const RING: [(i32, i32); 6] = [(-1, 0), (-1, 1), (0, 1), (1, 0), (1, -1), (0, -1)];
+
+/// What a player's stone on a cell would touch in one direction: a group of
+/// their stones or an empty cell, and the edges those reach.
+#[derive(Debug, Clone, Copy)]
+struct Reach {
+    cells: Bitboard,
+    start: bool,
+    end: bool,
+}
+
+impl Reach {
+    fn touches(&self, other: &Reach, size: usize) -> bool {
+        (self.start && other.start)
+            || (self.end && other.end)
+            || !(self.cells & (other.cells | other.cells.neighbors(size))).is_empty()
+    }
+}
+
+/// The stones of a position, cheap to copy: trying a stone somewhere does
+/// not clone the board.
+#[derive(Debug, Clone, Copy)]
+struct Stones {
+    size: usize,
+    white: Bitboard,
+    black: Bitboard,
+}
+
+impl Stones {
+    fn new(board: &Board) -> Stones {
+        Stones {
+            size: board.size(),
+            white: board.stones(Color::White),
+            black: board.stones(Color::Black),
+        }
+    }
+
+    fn of(&self, color: Color) -> Bitboard {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+            Color::None => Bitboard::full(self.size).and_not(self.white | self.black),
+        }
+    }
+
+    fn get(&self, index: usize) -> Color {
+        if self.white.get(index) {
+            Color::White
+        } else if self.black.get(index) {
+            Color::Black
+        } else {
+            Color::None
+        }
+    }
+
+    /// The same stones with one of `color` added at `index`.
+    fn with(mut self, color: Color, index: usize) -> Stones {
+        match color {
+            Color::White => self.white.set(index),
+            Color::Black => self.black.set(index),
+            Color::None => {}
+        }
+        self
+    }
+
+    fn empty_around(&self, index: usize) -> Bitboard {
+        let mut cell = Bitboard::empty();
+        cell.set(index);
+        cell.neighbors(self.size) & self.of(Color::None)
+    }
+
+    /// See `is_dead`.
+    fn is_dead(&self, index: usize) -> bool {
+        // Empty cells count for both players, and two of them around the cell
+        // never touch unless next to each other: more than two is never dead.
+        self.get(index) == Color::None
+            && self.empty_around(index).count() <= 2
+            && !self.links(Color::Black, index)
+            && !self.links(Color::White, index)
+    }
+
+    /// Whether a stone of `color` on the empty cell at `index` could join
+    /// two of its surroundings that are not already joined.
+    fn links(&self, color: Color, index: usize) -> bool {
+        let size = self.size as i32;
+        let (x, y) = Bitboard::coord(self.size, index);
+        let last = self.size - 1;
+        let (start_edge, end_edge) = match color {
+            Color::Black => (Bitboard::row(self.size, 0), Bitboard::row(self.size, last)),
+            _ => (
+                Bitboard::column(self.size, 0),
+                Bitboard::column(self.size, last),
+            ),
+        };
+        let stones = self.of(color);
+
+        let around: Vec<(usize, Reach)> = RING
+            .iter()
+            .enumerate()
+            .filter_map(|(position, (dx, dy))| {
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                let line = match color {
+                    Color::Black => nx,
+                    _ => ny,
+                };
+                if !(0..size).contains(&nx) || !(0..size).contains(&ny) {
+                    // Off the board: one of the player's edges, or the opponent's.
+                    let reach = Reach {
+                        cells: Bitboard::empty(),
+                        start: line < 0,
+                        end: line >= size,
+                    };
+                    return (reach.start || reach.end).then_some((position, reach));
+                }
+
+                let neighbor = Bitboard::index(self.size, nx as usize, ny as usize);
+                let mut cells = Bitboard::empty();
+                cells.set(neighbor);
+                match self.get(neighbor) {
+                    Color::None => {}
+                    stone if stone == color => cells = cells.flood_fill(stones, self.size),
+                    _ => return None,
+                }
+                let reach = Reach {
+                    cells,
+                    start: !(cells & start_edge).is_empty(),
+                    end: !(cells & end_edge).is_empty(),
+                };
+                Some((position, reach))
+            })
+            .collect();
+
+        around.iter().enumerate().any(|(i, (a, reach_a))| {
+            around[i + 1..].iter().any(|(b, reach_b)| {
+                let consecutive = b - a == 1 || b - a == RING.len() - 1;
+                !consecutive && !reach_a.touches(reach_b, self.size)
+            })
+        })
+    }
+
+    /// Whether a stone of `color` on `killer` leaves `cell` dead.
+    fn kills(&self, color: Color, killer: usize, cell: usize) -> bool {
+        self.with(color, killer).is_dead(cell)
+    }
+
+    fn dead(&self) -> Bitboard {
+        let mut dead = Bitboard::empty();
+        for index in self.of(Color::None).iter() {
+            if self.is_dead(index) {
+                dead.set(index);
+            }
+        }
+        dead
+    }
+
+    /// See `captured_pairs`, by cell index.
+    fn captured_pairs(&self, color: Color) -> Vec<(usize, usize)> {
+        // Once the other cell is taken, each one needs at most two empty
+        // neighbors left to be dead.
+        let mut candidates = Bitboard::empty();
+        for index in self.of(Color::None).iter() {
+            if self.empty_around(index).count() <= 3 {
+                candidates.set(index);
+            }
+        }
+
+        let mut pairs = Vec::new();
+        for a in candidates.iter() {
+            for b in (self.empty_around(a) & candidates).iter() {
+                if a < b && self.kills(color, b, a) && self.kills(color, a, b) {
+                    pairs.push((a, b));
+                }
+            }
+        }
+        pairs
+    }
+}
+
+/// An empty cell is dead when no set of cells connecting a player's edges
+/// needs it, for either player: its content cannot change the outcome.
+///
+/// This is the local test: for each player, everything the cell touches
+/// (their groups, empty cells and edges) is already pairwise adjacent or
+/// connected, so a path through the cell can always skip it.
+pub fn is_dead(board: &Board, x: usize, y: usize) -> bool {
+    Stones::new(board).is_dead(Bitboard::index(board.size(), x, y))
+}
+
+/// Every dead cell of the board.
+pub fn dead_cells(board: &Board) -> Vec<(usize, usize)> {
+    Stones::new(board)
+        .dead()
+        .iter()
+        .map(|index| Bitboard::coord(board.size(), index))
+        .collect()
+}
+
+/// Pairs of adjacent empty cells `color` can keep for itself: whichever
+/// one the opponent takes, `color` answers in the other and the opponent's
+/// stone is dead. Both cells can be filled with `color`.
+pub fn captured_pairs(board: &Board, color: Color) -> Vec<((usize, usize), (usize, usize))> {
+    Stones::new(board)
+        .captured_pairs(color)
+        .into_iter()
+        .map(|(a, b)| {
+            (
+                Bitboard::coord(board.size(), a),
+                Bitboard::coord(board.size(), b),
+            )
+        })
+        .collect()
+}
+
+/// Cells of all the pairs `color` captures.
+pub fn captured_cells(board: &Board, color: Color) -> Vec<(usize, usize)> {
+    let mut cells: Vec<(usize, usize)> = captured_pairs(board, color)
+        .into_iter()
+        .flat_map(|(a, b)| [a, b])
+        .collect();
+    cells.sort();
+    cells.dedup();
+    cells
+}
+
+/// Fills dead cells, given to `color` as their owner does not matter, and
+/// captured pairs, given to the player capturing them, until none is left.
+/// The result has the same value as the position, with fewer moves to
+/// look at.
+pub fn fill_captured(board: &Board, color: Color) -> Board {
+    let mut board = board.clone();
+    loop {
+        if board.winner().is_some() {
+            return board;
+        }
+
+        let stones = Stones::new(&board);
+        let dead = stones.dead();
+        for index in dead.iter() {
+            let (x, y) = Bitboard::coord(board.size(), index);
+            board.place_stone(color, x, y);
+        }
+        if !dead.is_empty() {
+            continue;
+        }
+
+        // Captured pairs may overlap, so they are filled one at a time.
+        let captured = [color, color.opponent()].into_iter().find_map(|owner| {
+            stones
+                .captured_pairs(owner)
+                .first()
+                .map(|&pair| (owner, pair))
+        });
+        match captured {
+            Some((owner, (a, b))) => {
+                for index in [a, b] {
+                    let (x, y) = Bitboard::coord(board.size(), index);
+                    board.place_stone(owner, x, y);
+                }
+            }
+            None => return board,
+        }
+    }
+}
+
+/// Moves worth searching for the side to move: dead and captured cells are
+/// left out, as are moves dominated by a neighboring one. A move at `k`
+/// dominates a move at `c` when the player's stone at `k` leaves `c` dead:
+/// `c` can no longer matter, while `k` may. Dead and captured cells are
+/// filled once for the position and each domination is a test on the
+/// stones, so the board is never cloned per candidate. Falls back to every
+/// move when nothing is left.
+pub fn pruned_moves(board: &Board) -> Vec<(usize, usize)> {
+    let color = board.next_color();
+    let stones = Stones::new(&fill_captured(board, color));
+    let empty = stones.of(Color::None);
+
+    // A cell only dominates others while it is kept itself, so that of
+    // cells dominating each other one is always left.
+    let mut dominated = Bitboard::empty();
+    for c in empty.iter() {
+        if stones
+            .empty_around(c)
+            .and_not(dominated)
+            .iter()
+            .any(|k| stones.kills(color, k, c))
+        {
+            dominated.set(c);
+        }
+    }
+
+    let moves: Vec<(usize, usize)> = empty
+        .and_not(dominated)
+        .iter()
+        .map(|index| Bitboard::coord(board.size(), index))
+        .collect();
+
+    if moves.is_empty() {
+        board.possible_moves()
+    } else {
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(size: usize, white: &[(usize, usize)], black: &[(usize, usize)]) -> Board {
+        let mut board = Board::new(size);
+        for &(x, y) in white {
+            board.place_stone(Color::White, x, y);
+        }
+        for &(x, y) in black {
+            board.place_stone(Color::Black, x, y);
+        }
+        board
+    }
+
+    #[test]
+    fn dead() {
+        // b2 surrounded by four Black stones in a row and two White ones.
+        let board = board(4, &[(2, 1), (2, 0)], &[(0, 1), (0, 2), (1, 2), (1, 0)]);
+        assert!(is_dead(&board, 1, 1));
+        assert!(!is_dead(&Board::new(4), 1, 1));
+        assert_eq!(dead_cells(&Board::new(4)), vec![]);
+    }
+
+    #[test]
+    fn dead_on_the_edge() {
+        // a1 only touches the top edge and two White stones next to each
+        // other: nobody needs it.
+        let board = board(4, &[(0, 1), (1, 0)], &[]);
+        assert!(is_dead(&board, 0, 0));
+        assert!(!is_dead(&board, 0, 2));
+    }
+
+    #[test]
+    fn captured() {
+        // b1 and c1, between White's a1 and d1 and above Black's a2 b2 c2:
+        // whichever one White takes, Black takes the other.
+        let board = board_with_edge_pair();
+        assert_eq!(captured_pairs(&board, Color::Black), vec![((0, 1), (0, 2))]);
+        assert_eq!(captured_cells(&board, Color::White), vec![]);
+        let filled = fill_captured(&board, Color::White);
+        assert_eq!(filled.get(0, 1), Color::Black);
+        assert_eq!(filled.get(0, 2), Color::Black);
+    }
+
+    fn board_with_edge_pair() -> Board {
+        board(4, &[(0, 0), (0, 3)], &[(1, 1), (1, 0), (1, 2)])
+    }
+
+    #[test]
+    fn pruning() {
+        let board = Board::new(3);
+        assert!(!pruned_moves(&board).is_empty());
+
+        let mut board = board_with_edge_pair();
+        board.play(3, 3);
+        let moves = pruned_moves(&board);
+        assert!(!moves.contains(&(0, 1)) && !moves.contains(&(0, 2)));
+        assert!(moves.len() < board.possible_moves().len());
+    }
+}
@@ -1,13 +1,33 @@
 pub mod best_list;
+mod bitboard;
 pub mod board;
-mod cell;
+pub mod cell;
+mod circuit;
 pub mod color;
-mod distance;
+pub mod diagram;
+#[cfg(test)]
+mod differential;
+pub mod distance;
+mod distance_map;
+pub mod error;
 pub mod evaluation;
-mod game;
+mod flow;
+pub mod game;
+pub mod group;
 mod gui;
-mod log;
-mod player;
-mod score;
-mod strategy;
-mod tournament;
+pub mod hex_move;
+pub mod inferior;
+pub mod log;
+pub mod move_list;
+pub mod player;
+pub mod position;
+pub mod score;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod sgf;
+pub mod strategy;
+pub mod symmetry;
+pub mod tournament;
+mod union_find;
+pub mod vc;
+mod zobrist;
@@ -1,30 +1,48 @@
 #![allow(unused_imports)]
 
-mod best_list;
-mod board;
-mod cell;
-mod color;
-mod distance;
-mod evaluation;
-mod game;
-mod gui;
-mod log;
-mod player;
-mod score;
-mod strategy;
-mod tournament;
-
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::time::Duration;
 
-use color::Color;
-use evaluation::*;
-use game::Game;
-use log::{LogFlag, LogLevel};
-use player::Player;
-use strategy::*;
-use tournament::Tournament;
+use rust_hex::board::{Board, DEFAULT_BOARD_SIZE, MAX_BOARD_SIZE, MIN_BOARD_SIZE};
+use rust_hex::color::Color;
+use rust_hex::evaluation::*;
+use rust_hex::game::Game;
+use rust_hex::log::{LogFlag, LogLevel};
+use rust_hex::player::Player;
+use rust_hex::strategy::*;
+use rust_hex::tournament::Tournament;
+
+struct Options {
+    board_size: usize,
+    swap_rule: bool,
+}
+
+/// Reads `[--swap] [SIZE]` from the command line.
+fn options() -> Result<Options, String> {
+    let mut options = Options {
+        board_size: DEFAULT_BOARD_SIZE,
+        swap_rule: false,
+    };
+    let mut size_given = false;
+    for arg in std::env::args().skip(1) {
+        if arg == "--swap" {
+            options.swap_rule = true;
+            continue;
+        }
+        if size_given {
+            return Err(format!("unexpected argument '{}'", arg));
+        }
+        let size = arg
+            .parse()
+            .map_err(|_| format!("'{}' is not a board size", arg))?;
+        options.board_size = Board::try_new(size)
+            .map(|board| board.size())
+            .map_err(|error| error.to_string())?;
+        size_given = true;
+    }
+    Ok(options)
+}
 
 #[allow(clippy::vec_init_then_push)]
 fn main() {
@@ -40,6 +58,15 @@ fn main() {
 
     let duration = Duration::from_millis(1000);
 
+    let options = options().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        eprintln!(
+            "usage: rust_hex [--swap] [SIZE], with a board size between {} and {}, {} by default",
+            MIN_BOARD_SIZE, MAX_BOARD_SIZE, DEFAULT_BOARD_SIZE
+        );
+        std::process::exit(2);
+    });
+
     let mut players: HashMap<Color, Rc<Player>> = HashMap::new();
     players.insert(
         Color::Black,
@@ -79,9 +106,13 @@ fn main() {
     );
 
     let mut hex = Game::new(players);
+    hex.set_board_size(options.board_size);
     hex.set_duration(duration);
+    hex.set_swap_rule(options.swap_rule);
     hex.set_log_level(log_level);
-    hex.play();
+    if let Err(error) = hex.play() {
+        println!("Game aborted: {}", error);
+    }
 
     /*
 
@@ -113,7 +144,7 @@ fn main() {
 
     let mut tournament = Tournament::new();
     tournament.set_players(players);
-    tournament.create_games(7, 10);
+    tournament.create_games(options.board_size, 10);
     tournament.play();
     tournament.print_results();
     */
@@ -142,7 +173,7 @@ fn main() {
     )));
     let mut tournament = Tournament::new();
     tournament.set_players(players);
-    tournament.create_games(5, 5);
+    tournament.create_games(options.board_size, 5);
     tournament.play();
     tournament.print_results();
     */
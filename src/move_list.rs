@@ -0,0 +1,202 @@
+//! Games as plain lists of moves in the usual notation, as pasted from
+//! forums and other programs: `1. a3 2. swap 3. c2 4. resign`. Move numbers
+//! are optional, and moves may be separated by spaces, commas or
+//! semicolons. The board size is not part of the list.
+
+use std::fmt;
+
+use crate::board::{MAX_BOARD_SIZE, MIN_BOARD_SIZE};
+use crate::error::HexError;
+use crate::sgf::{GameRecord, RecordedMove};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseMoveListError {
+    /// The board size is not a supported one.
+    InvalidSize(usize),
+    /// Move `number` (1-based) is not a cell, `swap` or `resign`.
+    InvalidMove {
+        number: usize,
+        value: String,
+    },
+    /// A move number out of sequence before move `expected`.
+    MoveNumber {
+        expected: usize,
+        found: String,
+    },
+    IllegalMove {
+        number: usize,
+        error: HexError,
+    },
+}
+
+impl GameRecord {
+    /// The moves separated by spaces, without numbers.
+    pub fn to_move_list(&self) -> String {
+        self.moves
+            .iter()
+            .map(|played| played.mv.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Reads the moves of a game on a board of the given size, checking that
+    /// they can be played. The winner comes from the moves.
+    pub fn from_move_list(size: usize, text: &str) -> Result<GameRecord, ParseMoveListError> {
+        if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) {
+            return Err(ParseMoveListError::InvalidSize(size));
+        }
+        let mut record = GameRecord::new(size);
+        let tokens = text
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+            .filter(|token| !token.is_empty());
+        for token in tokens {
+            let number = record.moves.len() + 1;
+            // A number, possibly stuck to its move as in `1.a3`.
+            let value = match token.split_once('.') {
+                Some((prefix, value)) if prefix.chars().all(|c| c.is_ascii_digit()) => {
+                    if prefix != number.to_string() {
+                        return Err(ParseMoveListError::MoveNumber {
+                            expected: number,
+                            found: prefix.to_string(),
+                        });
+                    }
+                    value
+                }
+                _ => token,
+            };
+            if value.is_empty() {
+                continue;
+            }
+
+            let mv = value.parse().map_err(|_| ParseMoveListError::InvalidMove {
+                number,
+                value: value.to_string(),
+            })?;
+            record.moves.push(RecordedMove { mv, comment: None });
+        }
+
+        let board = record
+            .replay()
+            .map_err(|(index, error)| ParseMoveListError::IllegalMove {
+                number: index + 1,
+                error,
+            })?;
+        record.winner = record.outcome(&board);
+        Ok(record)
+    }
+}
+
+impl fmt::Display for ParseMoveListError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseMoveListError::InvalidSize(size) => write!(
+                f,
+                "size {} is not between {} and {}",
+                size, MIN_BOARD_SIZE, MAX_BOARD_SIZE
+            ),
+            ParseMoveListError::InvalidMove { number, value } => write!(
+                f,
+                "move {} '{}' is not a move, expected a cell like c3, swap or resign",
+                number, value
+            ),
+            ParseMoveListError::MoveNumber { expected, found } => {
+                write!(f, "move number {} instead of {}", found, expected)
+            }
+            ParseMoveListError::IllegalMove { number, error } => {
+                write!(f, "move {} is illegal: {}", number, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseMoveListError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::hex_move::Move;
+
+    #[test]
+    fn read_and_write() {
+        let record = GameRecord::from_move_list(3, "1. b1 2. swap 3.b2, 4. resign").unwrap();
+        assert_eq!(
+            record
+                .moves
+                .iter()
+                .map(|played| played.mv)
+                .collect::<Vec<_>>(),
+            [Move::Cell(0, 1), Move::Swap, Move::Cell(1, 1), Move::Resign]
+        );
+        assert_eq!(record.winner, Some(Color::White));
+        assert_eq!(record.to_move_list(), "b1 swap b2 resign");
+        assert_eq!(
+            GameRecord::from_move_list(3, &record.to_move_list()),
+            Ok(record)
+        );
+
+        // Unfinished, then won by Black along the b column.
+        let record = GameRecord::from_move_list(3, "a1 a2; b1 b2; a3 c2").unwrap();
+        assert_eq!(record.winner, None);
+        let record = GameRecord::from_move_list(3, "a1 b1 a2 b2 c3 b3").unwrap();
+        assert_eq!(record.winner, Some(Color::Black));
+        assert_eq!(GameRecord::from_move_list(3, "").unwrap().moves, []);
+    }
+
+    #[test]
+    fn errors() {
+        use ParseMoveListError::*;
+
+        let cases = [
+            (
+                "a1 z",
+                InvalidMove {
+                    number: 2,
+                    value: "z".to_string(),
+                },
+            ),
+            (
+                "1. a1 3. b2",
+                MoveNumber {
+                    expected: 2,
+                    found: "3".to_string(),
+                },
+            ),
+            (
+                "a1 a1",
+                IllegalMove {
+                    number: 2,
+                    error: HexError::Occupied { x: 0, y: 0 },
+                },
+            ),
+            (
+                "a1 d1",
+                IllegalMove {
+                    number: 2,
+                    error: HexError::OutOfBounds { x: 0, y: 3 },
+                },
+            ),
+            (
+                "a1 b1 a2 b2 c3 b3 c1",
+                IllegalMove {
+                    number: 7,
+                    error: HexError::GameFinished,
+                },
+            ),
+        ];
+        for (input, error) in cases {
+            assert_eq!(
+                GameRecord::from_move_list(3, input),
+                Err(error),
+                "{:?}",
+                input
+            );
+        }
+
+        assert_eq!(GameRecord::from_move_list(0, "a1"), Err(InvalidSize(0)));
+        assert_eq!(
+            GameRecord::from_move_list(MAX_BOARD_SIZE + 1, ""),
+            Err(InvalidSize(MAX_BOARD_SIZE + 1))
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::{rc::Rc, time::Duration};
 
-use crate::{board::Board, strategy::Strategy};
+use crate::{board::Board, hex_move::Move, strategy::Strategy};
 
 #[derive(Clone)]
 pub struct Player {
@@ -20,9 +20,13 @@ impl Player {
         }
     }
 
-    pub fn next_move(&self, board: &Board) -> (usize, usize) {
+    pub fn next_move(&self, board: &Board) -> Move {
         self.strategy.next_move(board, self.time_by_move)
     }
+
+    pub fn swap(&self, board: &Board) -> bool {
+        self.strategy.swap(board, self.time_by_move)
+    }
 }
 
 impl std::fmt::Debug for Player {
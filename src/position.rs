@@ -0,0 +1,311 @@
+//! One-line text encoding of a position, in the spirit of chess FEN.
+//!
+//! Rows are listed from the top (row 1) down and separated by `/`. In each
+//! row, `w` is a White stone, `b` a Black stone and a number a run of empty
+//! cells. The side to move follows after a space, as `w` or `b`:
+//!
+//! ```text
+//! 3/1w1/b2 b
+//! ```
+//!
+//! is a 3x3 board with White on b2, Black on a3 and Black to move. The
+//! board size is the number of rows. The move history is not part of the
+//! position.
+
+use std::fmt;
+
+use crate::board::{Board, MAX_BOARD_SIZE, MIN_BOARD_SIZE};
+use crate::color::Color;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePositionError {
+    Empty,
+    /// The number of rows is not a supported board size.
+    InvalidSize(usize),
+    /// Row `row` (1-based) holds `found` cells instead of `expected`.
+    RowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// Something else than `w`, `b` or a number of empty cells in a row.
+    InvalidCharacter {
+        row: usize,
+        found: char,
+    },
+    /// A run of zero empty cells.
+    EmptyRun {
+        row: usize,
+    },
+    MissingSideToMove,
+    InvalidSideToMove(String),
+    TrailingInput(String),
+}
+
+impl Board {
+    /// The position in the format described in the [`position`](crate::position)
+    /// module.
+    pub fn to_position(&self) -> String {
+        let mut position = String::new();
+        for x in 0..self.size() {
+            if x > 0 {
+                position.push('/');
+            }
+            let mut empty = 0;
+            for y in 0..self.size() {
+                let stone = match self.get(x, y) {
+                    Color::White => 'w',
+                    Color::Black => 'b',
+                    Color::None => {
+                        empty += 1;
+                        continue;
+                    }
+                };
+                if empty > 0 {
+                    position.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                position.push(stone);
+            }
+            if empty > 0 {
+                position.push_str(&empty.to_string());
+            }
+        }
+        position.push(' ');
+        position.push(side_char(self.next_color()));
+        position
+    }
+
+    /// Reads a position written by [`Board::to_position`]. Surrounding
+    /// whitespace is ignored. The board has no history, so it cannot be
+    /// undone past the position or swapped.
+    pub fn from_position(text: &str) -> Result<Board, ParsePositionError> {
+        let mut fields = text.split_whitespace();
+        let rows = fields.next().ok_or(ParsePositionError::Empty)?;
+        let side = fields.next().ok_or(ParsePositionError::MissingSideToMove)?;
+        if let Some(trailing) = fields.next() {
+            return Err(ParsePositionError::TrailingInput(trailing.to_string()));
+        }
+
+        let rows: Vec<&str> = rows.split('/').collect();
+        let size = rows.len();
+        if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) {
+            return Err(ParsePositionError::InvalidSize(size));
+        }
+
+        let mut stones = Vec::new();
+        for (x, row) in rows.iter().enumerate() {
+            let cells = parse_row(row, x + 1)?;
+            if cells.len() != size {
+                return Err(ParsePositionError::RowLength {
+                    row: x + 1,
+                    expected: size,
+                    found: cells.len(),
+                });
+            }
+            stones.extend(
+                cells
+                    .into_iter()
+                    .enumerate()
+                    .filter(|&(_, color)| color != Color::None)
+                    .map(|(y, color)| (color, x, y)),
+            );
+        }
+
+        let next_color = match side {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(ParsePositionError::InvalidSideToMove(side.to_string())),
+        };
+
+        let mut board = Board::new(size);
+        for (color, x, y) in stones {
+            board.place_stone(color, x, y);
+        }
+        board.set_next_color(next_color);
+        Ok(board)
+    }
+}
+
+fn side_char(color: Color) -> char {
+    match color {
+        Color::Black => 'b',
+        _ => 'w',
+    }
+}
+
+/// Cells of a row, stopping early once it is longer than any board.
+fn parse_row(row: &str, number: usize) -> Result<Vec<Color>, ParsePositionError> {
+    let mut cells = Vec::new();
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            'w' => cells.push(Color::White),
+            'b' => cells.push(Color::Black),
+            '0'..='9' => {
+                let mut run = c.to_digit(10).unwrap() as usize;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    run = run.saturating_mul(10).saturating_add(digit as usize);
+                    chars.next();
+                }
+                if run == 0 {
+                    return Err(ParsePositionError::EmptyRun { row: number });
+                }
+                let run = run.min(MAX_BOARD_SIZE + 1);
+                cells.extend(std::iter::repeat_n(Color::None, run));
+            }
+            _ => {
+                return Err(ParsePositionError::InvalidCharacter {
+                    row: number,
+                    found: c,
+                });
+            }
+        }
+        if cells.len() > MAX_BOARD_SIZE {
+            break;
+        }
+    }
+    Ok(cells)
+}
+
+impl fmt::Display for ParsePositionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParsePositionError::Empty => write!(f, "the position is empty"),
+            ParsePositionError::InvalidSize(size) => write!(
+                f,
+                "{} rows, a board has between {} and {}",
+                size, MIN_BOARD_SIZE, MAX_BOARD_SIZE
+            ),
+            ParsePositionError::RowLength {
+                row,
+                expected,
+                found,
+            } => {
+                // Rows cut short at the largest board read as "more than".
+                let more = if *found > MAX_BOARD_SIZE {
+                    "more than "
+                } else {
+                    ""
+                };
+                write!(
+                    f,
+                    "row {} has {}{} cells instead of {}",
+                    row,
+                    more,
+                    found.min(&MAX_BOARD_SIZE),
+                    expected
+                )
+            }
+            ParsePositionError::InvalidCharacter { row, found } => write!(
+                f,
+                "'{}' in row {}, expected w, b or a number of empty cells",
+                found, row
+            ),
+            ParsePositionError::EmptyRun { row } => {
+                write!(f, "a run of zero empty cells in row {}", row)
+            }
+            ParsePositionError::MissingSideToMove => {
+                write!(f, "the side to move is missing after the rows")
+            }
+            ParsePositionError::InvalidSideToMove(side) => {
+                write!(f, "'{}' is not a side to move, expected w or b", side)
+            }
+            ParsePositionError::TrailingInput(input) => {
+                write!(f, "unexpected '{}' after the side to move", input)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParsePositionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        assert_eq!(Board::new(3).to_position(), "3/3/3 w");
+
+        let mut board = Board::new(3);
+        board.play(1, 1);
+        board.play(2, 0);
+        board.play(0, 2);
+        assert_eq!(board.to_position(), "2w/1w1/b2 b");
+
+        let mut board = Board::new(11);
+        board.play(0, 10);
+        assert_eq!(board.to_position(), "10w/11/11/11/11/11/11/11/11/11/11 b");
+    }
+
+    #[test]
+    fn parse() {
+        let board = Board::from_position(" 3/1w1/b2 b\n").unwrap();
+        assert_eq!(board.size(), 3);
+        assert_eq!(board.get(1, 1), Color::White);
+        assert_eq!(board.get(2, 0), Color::Black);
+        assert_eq!(board.next_color(), Color::Black);
+        assert!(board.moves().is_empty());
+
+        let board = Board::from_position("ww/bb b").unwrap();
+        assert_eq!(board.winner(), Some(Color::White));
+    }
+
+    #[test]
+    fn round_trip() {
+        for size in [MIN_BOARD_SIZE, 7, 11, MAX_BOARD_SIZE] {
+            for nb_moves in [0, size, size * size / 2] {
+                let board = Board::random_board(size, nb_moves);
+                let parsed = Board::from_position(&board.to_position()).unwrap();
+                assert_eq!(parsed, board);
+                assert_eq!(parsed.key(), board.key());
+                assert_eq!(parsed.winner(), board.winner());
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        use ParsePositionError::*;
+
+        let cases = [
+            ("", Empty),
+            ("3/3/3", MissingSideToMove),
+            ("3/3/3 w 12", TrailingInput("12".to_string())),
+            ("3/3/3 x", InvalidSideToMove("x".to_string())),
+            ("1 w", InvalidSize(1)),
+            ("3/3/3/3/3/3/3/3/3/3/3/3/3/3/3/3/3/3/3/3 w", InvalidSize(20)),
+            (
+                "3/2/3 w",
+                RowLength {
+                    row: 2,
+                    expected: 3,
+                    found: 2,
+                },
+            ),
+            (
+                "3/3/99999999999999999999 w",
+                RowLength {
+                    row: 3,
+                    expected: 3,
+                    found: MAX_BOARD_SIZE + 1,
+                },
+            ),
+            ("3/1W1/3 w", InvalidCharacter { row: 2, found: 'W' }),
+            ("3/3/0b2 w", EmptyRun { row: 3 }),
+        ];
+        for (input, error) in cases {
+            assert_eq!(Board::from_position(input), Err(error), "{:?}", input);
+        }
+
+        assert_eq!(
+            Board::from_position("3/2/3 w").unwrap_err().to_string(),
+            "row 2 has 2 cells instead of 3"
+        );
+        assert_eq!(
+            Board::from_position("3/3/40 w").unwrap_err().to_string(),
+            "row 3 has more than 19 cells instead of 3"
+        );
+    }
+}
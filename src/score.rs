@@ -166,10 +166,10 @@ impl Neg for Score {
         match self {
             Score::BlackCheckMate => Score::WhiteCheckMate,
             Score::Undefined => Score::Undefined,
-            Score::WhiteCheckMate => Score::WhiteCheckMate,
+            Score::WhiteCheckMate => Score::BlackCheckMate,
             Score::Advantage(a) => Score::Advantage(-a),
-            Score::BlackMateIn(_) => Score::Undefined,
-            Score::WhiteMateIn(_) => Score::Undefined,
+            Score::BlackMateIn(n) => Score::WhiteMateIn(n),
+            Score::WhiteMateIn(n) => Score::BlackMateIn(n),
         }
     }
 }
@@ -219,6 +219,15 @@ mod tests {
         assert_eq!(score, Score::BlackMateIn(1));
     }
 
+    #[test]
+    fn neg() {
+        assert_eq!(-Score::Advantage(1.5), Score::Advantage(-1.5));
+        assert_eq!(-Score::BlackMateIn(2), Score::WhiteMateIn(2));
+        assert_eq!(-Score::WhiteMateIn(3), Score::BlackMateIn(3));
+        assert_eq!(-Score::BlackCheckMate, Score::WhiteCheckMate);
+        assert_eq!(-Score::WhiteCheckMate, Score::BlackCheckMate);
+    }
+
     #[test]
     fn iterator() {
         let score = Score::BlackCheckMate.next_back().unwrap();
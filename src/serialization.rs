@@ -0,0 +1,388 @@
+//! Serde support, behind the `serde` feature. The representations are meant
+//! to stay stable, for scripts and dashboards to rely on:
+//!
+//! - a color is `"white"`, `"black"` or `"none"`;
+//! - a move is its notation: `"c3"`, `"swap"` or `"resign"`;
+//! - a board is its rows, top first, with `w`, `b` and `.` for the cells,
+//!   along with the side to move, the moves played and the swap rule;
+//! - a score has a `kind`: `advantage` with a `value`, `mate` with the
+//!   `winner` and the number of `moves` to the win, or `checkmate` with the
+//!   `winner` once won;
+//! - a game is its [`GameRecord`](crate::sgf::GameRecord), and a tournament lists its players, the
+//!   wins of each player with White against each other player, and its
+//!   games.
+//!
+//! Games and tournaments hold strategies, so they can only be serialized.
+
+use serde::de::Error;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::board::{Board, MAX_BOARD_SIZE, MIN_BOARD_SIZE};
+use crate::color::Color;
+use crate::game::Game;
+use crate::hex_move::Move;
+use crate::score::Score;
+use crate::tournament::Tournament;
+
+impl Serialize for Move {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Move {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Move, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// A board size, rejecting the unsupported ones before anything builds a
+/// board from it.
+pub(crate) fn deserialize_size<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<usize, D::Error> {
+    let size = usize::deserialize(deserializer)?;
+    if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) {
+        return Err(D::Error::custom(format!(
+            "size {} is not between {} and {}",
+            size, MIN_BOARD_SIZE, MAX_BOARD_SIZE
+        )));
+    }
+    Ok(size)
+}
+
+#[derive(Serialize, Deserialize)]
+struct BoardRepr {
+    #[serde(deserialize_with = "deserialize_size")]
+    size: usize,
+    rows: Vec<String>,
+    next_color: Color,
+    moves: Vec<Move>,
+    swap_rule: bool,
+}
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let rows = (0..self.size())
+            .map(|x| {
+                (0..self.size())
+                    .map(|y| match self.get(x, y) {
+                        Color::White => 'w',
+                        Color::Black => 'b',
+                        Color::None => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        BoardRepr {
+            size: self.size(),
+            rows,
+            next_color: self.next_color(),
+            moves: self.moves().to_vec(),
+            swap_rule: self.swap_rule(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Boards with moves are replayed, so that they can be undone, and must end
+/// on the rows given. Boards without moves are set up from the rows.
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Board, D::Error> {
+        let repr = BoardRepr::deserialize(deserializer)?;
+        let size = repr.size;
+        if repr.next_color == Color::None {
+            return Err(D::Error::custom("the side to move must be white or black"));
+        }
+        if repr.rows.len() != size || repr.rows.iter().any(|row| row.chars().count() != size) {
+            return Err(D::Error::custom(format!(
+                "the rows do not make a {}x{} board",
+                size, size
+            )));
+        }
+
+        let mut setup = Board::new(size);
+        setup.set_swap_rule(repr.swap_rule);
+        for (x, row) in repr.rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                match cell {
+                    'w' => setup.place_stone(Color::White, x, y),
+                    'b' => setup.place_stone(Color::Black, x, y),
+                    '.' => {}
+                    _ => {
+                        return Err(D::Error::custom(format!(
+                            "'{}' in row {}, expected w, b or .",
+                            cell,
+                            x + 1
+                        )));
+                    }
+                }
+            }
+        }
+        setup.set_next_color(repr.next_color);
+        if repr.moves.is_empty() {
+            return Ok(setup);
+        }
+
+        let mut board = Board::new(size);
+        board.set_swap_rule(repr.swap_rule);
+        for (number, mv) in repr.moves.into_iter().enumerate() {
+            let result = match mv {
+                Move::Cell(x, y) => board.try_play(x, y),
+                Move::Swap => board.swap(),
+                Move::Resign => {
+                    return Err(D::Error::custom(format!(
+                        "move {} is {}, which is not played on the board",
+                        number + 1,
+                        mv
+                    )));
+                }
+            };
+            result.map_err(|error| {
+                D::Error::custom(format!(
+                    "move {} ({}) is illegal: {}",
+                    number + 1,
+                    mv,
+                    error
+                ))
+            })?;
+        }
+        if board != setup {
+            return Err(D::Error::custom("the moves do not lead to the rows"));
+        }
+        Ok(board)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum ScoreRepr {
+    Undefined,
+    Advantage { value: f64 },
+    Mate { winner: Color, moves: usize },
+    Checkmate { winner: Color },
+}
+
+impl Serialize for Score {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mate = |winner, moves| ScoreRepr::Mate { winner, moves };
+        let checkmate = |winner| ScoreRepr::Checkmate { winner };
+        match *self {
+            Score::Undefined => ScoreRepr::Undefined,
+            Score::Advantage(value) => ScoreRepr::Advantage { value },
+            Score::BlackMateIn(moves) => mate(Color::Black, moves),
+            Score::WhiteMateIn(moves) => mate(Color::White, moves),
+            Score::BlackCheckMate => checkmate(Color::Black),
+            Score::WhiteCheckMate => checkmate(Color::White),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Score {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Score, D::Error> {
+        Ok(match ScoreRepr::deserialize(deserializer)? {
+            ScoreRepr::Undefined => Score::Undefined,
+            ScoreRepr::Advantage { value } => Score::Advantage(value),
+            ScoreRepr::Checkmate {
+                winner: Color::Black,
+            } => Score::BlackCheckMate,
+            ScoreRepr::Checkmate {
+                winner: Color::White,
+            } => Score::WhiteCheckMate,
+            ScoreRepr::Mate {
+                winner: Color::Black,
+                moves,
+            } => Score::BlackMateIn(moves),
+            ScoreRepr::Mate {
+                winner: Color::White,
+                moves,
+            } => Score::WhiteMateIn(moves),
+            ScoreRepr::Mate {
+                winner: Color::None,
+                ..
+            }
+            | ScoreRepr::Checkmate {
+                winner: Color::None,
+            } => return Err(D::Error::custom("a mate needs a winner")),
+        })
+    }
+}
+
+impl Serialize for Game {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.record().serialize(serializer)
+    }
+}
+
+impl Serialize for Tournament {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let players: Vec<&str> = self
+            .players()
+            .iter()
+            .map(|player| player.name.as_str())
+            .collect();
+        let mut state = serializer.serialize_struct("Tournament", 3)?;
+        state.serialize_field("players", &players)?;
+        state.serialize_field("results", self.results())?;
+        state.serialize_field("games", self.games())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Player;
+    use crate::sgf::GameRecord;
+    use serde_json::json;
+    use std::rc::Rc;
+
+    #[test]
+    fn board() {
+        let mut board = Board::new(3);
+        board.set_swap_rule(true);
+        board.play(0, 1);
+        board.swap().unwrap();
+        board.play(2, 2);
+
+        let value = serde_json::to_value(&board).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "size": 3,
+                "rows": ["...", "b..", "..w"],
+                "next_color": "black",
+                "moves": ["b1", "swap", "c3"],
+                "swap_rule": true,
+            })
+        );
+        let read: Board = serde_json::from_value(value).unwrap();
+        assert_eq!(read, board);
+        assert_eq!(read.moves(), board.moves());
+
+        // Without moves, the rows are enough.
+        let setup: Board = serde_json::from_value(json!({
+            "size": 2,
+            "rows": ["w.", ".b"],
+            "next_color": "white",
+            "moves": [],
+            "swap_rule": false,
+        }))
+        .unwrap();
+        assert_eq!(setup.get(1, 1), Color::Black);
+
+        for wrong in [
+            json!({"size": 2, "rows": ["w."], "next_color": "white", "moves": [], "swap_rule": false}),
+            json!({"size": 2, "rows": ["w.", ".x"], "next_color": "white", "moves": [], "swap_rule": false}),
+            json!({"size": 2, "rows": ["w.", ".."], "next_color": "black", "moves": ["b1"], "swap_rule": false}),
+            json!({"size": 2, "rows": ["w.", ".."], "next_color": "black", "moves": ["z9"], "swap_rule": false}),
+            json!({"size": 2, "rows": ["w.", ".."], "next_color": "none", "moves": [], "swap_rule": false}),
+            json!({"size": 1, "rows": ["."], "next_color": "white", "moves": [], "swap_rule": false}),
+        ] {
+            assert!(serde_json::from_value::<Board>(wrong).is_err());
+        }
+
+        // Errors name the move entry that cannot be replayed.
+        let error = |moves| {
+            let value = json!({"size": 2, "rows": ["w.", "b."], "next_color": "white", "moves": moves, "swap_rule": false});
+            serde_json::from_value::<Board>(value)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error(json!(["a1", "a1"])),
+            "move 2 (a1) is illegal: a1 is already occupied"
+        );
+        assert_eq!(
+            error(json!(["a1", "resign"])),
+            "move 2 is resign, which is not played on the board"
+        );
+    }
+
+    #[test]
+    fn score() {
+        let cases = [
+            (
+                Score::Advantage(1.5),
+                json!({"kind": "advantage", "value": 1.5}),
+            ),
+            (
+                Score::WhiteMateIn(3),
+                json!({"kind": "mate", "winner": "white", "moves": 3}),
+            ),
+            (
+                Score::BlackMateIn(0),
+                json!({"kind": "mate", "winner": "black", "moves": 0}),
+            ),
+            (
+                Score::BlackCheckMate,
+                json!({"kind": "checkmate", "winner": "black"}),
+            ),
+            (Score::Undefined, json!({"kind": "undefined"})),
+        ];
+        for (score, value) in cases {
+            assert_eq!(serde_json::to_value(score).unwrap(), value);
+            assert_eq!(serde_json::from_value::<Score>(value).unwrap(), score);
+        }
+    }
+
+    #[test]
+    fn record() {
+        let mut record = GameRecord::new(3);
+        record.white = Some("AlphaBeta_4".to_string());
+        record.moves = GameRecord::from_move_list(3, "a1 b2 resign").unwrap().moves;
+        record.winner = Some(Color::White);
+
+        let value = serde_json::to_value(&record).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "size": 3,
+                "white": "AlphaBeta_4",
+                "black": null,
+                "moves": [{"move": "a1"}, {"move": "b2"}, {"move": "resign"}],
+                "winner": "white",
+            })
+        );
+        assert_eq!(serde_json::from_value::<GameRecord>(value).unwrap(), record);
+
+        for size in [0, MAX_BOARD_SIZE + 1] {
+            let wrong =
+                json!({"size": size, "white": null, "black": null, "moves": [], "winner": null});
+            assert!(serde_json::from_value::<GameRecord>(wrong).is_err());
+        }
+    }
+
+    #[test]
+    fn tournament() {
+        let mut tournament = Tournament::new();
+        tournament.set_players(vec![Rc::new(Player::default()), Rc::new(Player::default())]);
+        tournament.create_games(3, 1);
+        tournament.play();
+
+        let value = serde_json::to_value(&tournament).unwrap();
+        assert_eq!(value["players"].as_array().unwrap().len(), 2);
+        let wins: u64 = value["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|row| row.as_array().unwrap())
+            .map(|wins| wins.as_u64().unwrap())
+            .sum();
+        let games = value["games"].as_array().unwrap();
+        assert_eq!(games.len(), 4);
+        let white_wins = games
+            .iter()
+            .filter(|game| game["winner"] == "white")
+            .count();
+        assert_eq!(wins as usize, white_wins);
+        assert_eq!(
+            serde_json::from_value::<GameRecord>(games[0].clone()).unwrap(),
+            tournament.games()[0].record()
+        );
+    }
+}
@@ -0,0 +1,733 @@
+//! Game records in SGF (`FF[4]`, `GM[11]`), as read and written by HexGUI,
+//! and in the SGF of Little Golem.
+//!
+//! In HexGUI, Black moves first and connects the top and bottom edges.
+//! Here White does and connects the left and right edges, so files are
+//! written through the transposition that swaps colors: White's stone on
+//! `c2` is Black's `b3` in the file, and White's name goes in `PB`. The
+//! swap rule maps onto itself, and is written `swap-pieces`.
+//!
+//! Little Golem writes its first player as White, as here, cells as two
+//! letters, column then row (`cb` is `c2`), the swap rule as `swap` and the
+//! result as the winner's color only.
+//!
+//! Reading follows the main line of the game tree, checks that the moves
+//! can be played and that the result agrees with them. Setup properties
+//! are not supported.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::board::{Board, MAX_BOARD_SIZE, MIN_BOARD_SIZE};
+use crate::color::Color;
+use crate::error::HexError;
+use crate::hex_move::Move;
+use crate::symmetry::Symmetry;
+
+/// A game played from an empty board, with the players' names, the moves
+/// and their comments, and the result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameRecord {
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::serialization::deserialize_size")
+    )]
+    pub size: usize,
+    pub white: Option<String>,
+    pub black: Option<String>,
+    /// Moves in order, White first, ending with the resignation if any.
+    pub moves: Vec<RecordedMove>,
+    pub winner: Option<Color>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedMove {
+    #[cfg_attr(feature = "serde", serde(rename = "move"))]
+    pub mv: Move,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSgfError {
+    /// Malformed SGF, at byte `offset` of the input.
+    Syntax {
+        offset: usize,
+        expected: &'static str,
+    },
+    /// The `GM` property names another game than Hex.
+    UnsupportedGame(String),
+    UnsupportedProperty(String),
+    MissingSize,
+    InvalidSize(String),
+    /// Move `number` (1-based) is not a cell, `swap-pieces` or `resign`.
+    InvalidMove {
+        number: usize,
+        value: String,
+    },
+    /// Move `number` is played by `found` (`B` or `W`) out of turn.
+    OutOfTurn {
+        number: usize,
+        found: String,
+    },
+    IllegalMove {
+        number: usize,
+        error: HexError,
+    },
+    /// The result gives the game to `claimed`, the moves to `actual`.
+    ResultMismatch {
+        claimed: Color,
+        actual: Color,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    HexGui,
+    LittleGolem,
+}
+
+impl Dialect {
+    /// How the file sees the board, see the module documentation.
+    fn symmetry(self) -> Symmetry {
+        match self {
+            Dialect::HexGui => Symmetry::TransposeSwap,
+            Dialect::LittleGolem => Symmetry::Identity,
+        }
+    }
+
+    fn write_move(self, mv: Move, size: usize) -> String {
+        match (self, mv.transform(self.symmetry(), size)) {
+            (Dialect::HexGui, Move::Swap) => "swap-pieces".to_string(),
+            (Dialect::LittleGolem, Move::Cell(x, y)) => [y, x]
+                .into_iter()
+                .map(|coordinate| (b'a' + coordinate as u8) as char)
+                .collect(),
+            (_, mv) => mv.to_string(),
+        }
+    }
+
+    fn parse_move(self, value: &str, size: usize) -> Option<Move> {
+        let mv = match self {
+            Dialect::HexGui => value.parse().ok()?,
+            Dialect::LittleGolem => match value.as_bytes() {
+                b"swap" => Move::Swap,
+                b"resign" => Move::Resign,
+                &[column @ b'a'..=b'z', row @ b'a'..=b'z'] => {
+                    Move::Cell((row - b'a') as usize, (column - b'a') as usize)
+                }
+                _ => return None,
+            },
+        };
+        Some(mv.transform(self.symmetry(), size))
+    }
+
+    fn write_result(self, winner: Color, resigned: bool) -> String {
+        let winner = property(self.symmetry().color(winner));
+        match self {
+            Dialect::HexGui if resigned => format!("{}+Resign", winner),
+            Dialect::HexGui => format!("{}+", winner),
+            Dialect::LittleGolem => winner.to_string(),
+        }
+    }
+
+    /// The winner of a result written by either program, `None` for
+    /// anything else, such as a draw or an unknown result.
+    fn parse_result(self, value: &str) -> Option<Color> {
+        let winner = match value.trim().chars().next()? {
+            'B' => Color::Black,
+            'W' => Color::White,
+            _ => return None,
+        };
+        Some(self.symmetry().color(winner))
+    }
+}
+
+impl GameRecord {
+    pub fn new(size: usize) -> GameRecord {
+        GameRecord {
+            size,
+            white: None,
+            black: None,
+            moves: Vec::new(),
+            winner: None,
+        }
+    }
+
+    /// Whether the game ended with a resignation.
+    pub fn resigned(&self) -> bool {
+        matches!(
+            self.moves.last(),
+            Some(RecordedMove {
+                mv: Move::Resign,
+                ..
+            })
+        )
+    }
+
+    /// The final position, replaying the moves on an empty board.
+    pub fn board(&self) -> Result<Board, HexError> {
+        self.replay().map_err(|(_, error)| error)
+    }
+
+    /// Like `board`, also giving the index of the first illegal move.
+    pub(crate) fn replay(&self) -> Result<Board, (usize, HexError)> {
+        let mut board = Board::new(self.size);
+        board.set_swap_rule(self.moves.iter().any(|played| played.mv == Move::Swap));
+        let mut resigned = false;
+        for (index, played) in self.moves.iter().enumerate() {
+            let result = match played.mv {
+                _ if resigned => Err(HexError::GameFinished),
+                Move::Cell(x, y) => board.try_play(x, y),
+                Move::Swap => board.swap(),
+                Move::Resign if board.winner().is_some() => Err(HexError::GameFinished),
+                Move::Resign => {
+                    resigned = true;
+                    Ok(())
+                }
+            };
+            result.map_err(|error| (index, error))?;
+        }
+        Ok(board)
+    }
+
+    /// The winner according to the moves, from the final position: the
+    /// player connecting their edges, or the opponent of the one resigning.
+    pub(crate) fn outcome(&self, board: &Board) -> Option<Color> {
+        if self.resigned() {
+            Some(board.next_color().opponent())
+        } else {
+            board.winner()
+        }
+    }
+
+    pub fn to_sgf(&self) -> String {
+        self.write(Dialect::HexGui)
+    }
+
+    /// Reads the first game of an SGF file.
+    pub fn from_sgf(text: &str) -> Result<GameRecord, ParseSgfError> {
+        GameRecord::read(text, Dialect::HexGui)
+    }
+
+    pub fn to_little_golem(&self) -> String {
+        self.write(Dialect::LittleGolem)
+    }
+
+    /// Reads a game downloaded from Little Golem.
+    pub fn from_little_golem(text: &str) -> Result<GameRecord, ParseSgfError> {
+        GameRecord::read(text, Dialect::LittleGolem)
+    }
+
+    fn write(&self, dialect: Dialect) -> String {
+        let symmetry = dialect.symmetry();
+        let mut sgf = match dialect {
+            Dialect::HexGui => format!(
+                "(;FF[4]GM[11]AP[{}:{}]SZ[{}]",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION"),
+                self.size
+            ),
+            Dialect::LittleGolem => format!("(;FF[4]EV[hex]SZ[{}]", self.size),
+        };
+        for (color, name) in [(Color::White, &self.white), (Color::Black, &self.black)] {
+            if let Some(name) = name {
+                sgf += &format!("P{}[{}]", property(symmetry.color(color)), escape(name));
+            }
+        }
+        if let Some(winner) = self.winner {
+            sgf += &format!("RE[{}]", dialect.write_result(winner, self.resigned()));
+        }
+        sgf.push('\n');
+
+        let mut color = Color::White;
+        for played in &self.moves {
+            sgf += &format!(
+                ";{}[{}]",
+                property(symmetry.color(color)),
+                dialect.write_move(played.mv, self.size)
+            );
+            if let Some(comment) = &played.comment {
+                sgf += &format!("C[{}]", escape(comment));
+            }
+            sgf.push('\n');
+            color = color.opponent();
+        }
+        sgf.push_str(")\n");
+        sgf
+    }
+
+    fn read(text: &str, dialect: Dialect) -> Result<GameRecord, ParseSgfError> {
+        let symmetry = dialect.symmetry();
+        let mut parser = Parser {
+            chars: text.char_indices().peekable(),
+            len: text.len(),
+        };
+        parser.skip_whitespace();
+        let nodes = parser.game_tree()?;
+
+        // A game tree always has a node.
+        let root = &nodes[0];
+        let value = |name: &str| {
+            root.iter()
+                .find(|(ident, _)| ident == name)
+                .and_then(|(_, values)| values.first())
+        };
+        if let Some(game) = value("GM")
+            && game.trim() != "11"
+        {
+            return Err(ParseSgfError::UnsupportedGame(game.clone()));
+        }
+        let size = value("SZ").ok_or(ParseSgfError::MissingSize)?;
+        let size = parse_size(size).ok_or_else(|| ParseSgfError::InvalidSize(size.clone()))?;
+
+        let mut record = GameRecord::new(size);
+        record.white = value(&format!("P{}", property(symmetry.color(Color::White)))).cloned();
+        record.black = value(&format!("P{}", property(symmetry.color(Color::Black)))).cloned();
+
+        let mut color = Color::White;
+        for node in &nodes {
+            if let Some((ident, _)) = node
+                .iter()
+                .find(|(ident, _)| matches!(ident.as_str(), "AB" | "AW" | "AE"))
+            {
+                return Err(ParseSgfError::UnsupportedProperty(ident.clone()));
+            }
+            let Some((ident, values)) = node.iter().find(|(ident, _)| ident == "B" || ident == "W")
+            else {
+                continue;
+            };
+
+            let number = record.moves.len() + 1;
+            if *ident != property(symmetry.color(color)) {
+                return Err(ParseSgfError::OutOfTurn {
+                    number,
+                    found: ident.clone(),
+                });
+            }
+            let value = values.first().map(String::as_str).unwrap_or_default();
+            let mv = dialect
+                .parse_move(value, size)
+                .ok_or_else(|| ParseSgfError::InvalidMove {
+                    number,
+                    value: value.to_string(),
+                })?;
+
+            let comment = node
+                .iter()
+                .find(|(ident, _)| ident == "C")
+                .and_then(|(_, values)| values.first())
+                .cloned();
+            record.moves.push(RecordedMove { mv, comment });
+            color = color.opponent();
+        }
+
+        let board = record
+            .replay()
+            .map_err(|(index, error)| ParseSgfError::IllegalMove {
+                number: index + 1,
+                error,
+            })?;
+        let actual = record.outcome(&board);
+        let claimed = value("RE").and_then(|result| dialect.parse_result(result));
+        if let (Some(claimed), Some(actual)) = (claimed, actual)
+            && claimed != actual
+        {
+            return Err(ParseSgfError::ResultMismatch { claimed, actual });
+        }
+        // Games lost on time have a result but no winner on the board.
+        record.winner = claimed.or(actual);
+        Ok(record)
+    }
+}
+
+fn property(color: Color) -> &'static str {
+    match color {
+        Color::Black => "B",
+        _ => "W",
+    }
+}
+
+/// `11` or `11:11`: Hex boards are square.
+fn parse_size(value: &str) -> Option<usize> {
+    let (columns, rows) = value.split_once(':').unwrap_or((value, value));
+    let size = columns.trim().parse().ok()?;
+    (rows.trim().parse() == Ok(size) && (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size))
+        .then_some(size)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+type Node = Vec<(String, Vec<String>)>;
+
+struct Parser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    len: usize,
+}
+
+impl Parser<'_> {
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.len, |&(offset, _)| offset)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, c: char, expected: &'static str) -> Result<(), ParseSgfError> {
+        if self.peek() != Some(c) {
+            return Err(ParseSgfError::Syntax {
+                offset: self.offset(),
+                expected,
+            });
+        }
+        self.chars.next();
+        self.skip_whitespace();
+        Ok(())
+    }
+
+    /// The nodes of the main line: the sequence, then the first variation,
+    /// recursively. Other variations are checked and dropped.
+    fn game_tree(&mut self) -> Result<Vec<Node>, ParseSgfError> {
+        self.expect('(', "'('")?;
+        let mut nodes = Vec::new();
+        while self.peek() == Some(';') {
+            self.chars.next();
+            self.skip_whitespace();
+            nodes.push(self.node()?);
+        }
+        if nodes.is_empty() {
+            return Err(ParseSgfError::Syntax {
+                offset: self.offset(),
+                expected: "';'",
+            });
+        }
+        let mut first = true;
+        while self.peek() == Some('(') {
+            let variation = self.game_tree()?;
+            if first {
+                nodes.extend(variation);
+                first = false;
+            }
+        }
+        self.expect(')', "')'")?;
+        Ok(nodes)
+    }
+
+    fn node(&mut self) -> Result<Node, ParseSgfError> {
+        let mut properties = Vec::new();
+        while self.peek().is_some_and(|c| c.is_ascii_uppercase()) {
+            let mut ident = String::new();
+            while let Some(c) = self.peek().filter(char::is_ascii_uppercase) {
+                ident.push(c);
+                self.chars.next();
+            }
+            self.skip_whitespace();
+
+            let mut values = Vec::new();
+            while self.peek() == Some('[') {
+                self.chars.next();
+                values.push(self.value()?);
+                self.skip_whitespace();
+            }
+            if values.is_empty() {
+                return Err(ParseSgfError::Syntax {
+                    offset: self.offset(),
+                    expected: "'[' after a property name",
+                });
+            }
+            properties.push((ident, values));
+        }
+        Ok(properties)
+    }
+
+    /// A property value after its `[`, up to the closing `]`.
+    fn value(&mut self) -> Result<String, ParseSgfError> {
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, ']')) => return Ok(value),
+                // An escaped line break is a soft one and disappears.
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, '\n')) => {}
+                    Some((_, c)) => value.push(c),
+                    None => break,
+                },
+                Some((_, c)) => value.push(c),
+                None => break,
+            }
+        }
+        Err(ParseSgfError::Syntax {
+            offset: self.len,
+            expected: "']'",
+        })
+    }
+}
+
+impl fmt::Display for ParseSgfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseSgfError::Syntax { offset, expected } => {
+                write!(f, "expected {} at byte {}", expected, offset)
+            }
+            ParseSgfError::UnsupportedGame(game) => {
+                write!(f, "GM[{}] is not Hex, which is GM[11]", game)
+            }
+            ParseSgfError::UnsupportedProperty(ident) => {
+                write!(f, "the {} property is not supported", ident)
+            }
+            ParseSgfError::MissingSize => write!(f, "the board size (SZ) is missing"),
+            ParseSgfError::InvalidSize(size) => write!(
+                f,
+                "SZ[{}] is not a board size between {} and {}",
+                size, MIN_BOARD_SIZE, MAX_BOARD_SIZE
+            ),
+            ParseSgfError::InvalidMove { number, value } => {
+                write!(f, "move {} '{}' is not a move", number, value)
+            }
+            ParseSgfError::OutOfTurn { number, found } => {
+                write!(f, "move {} is played by {} out of turn", number, found)
+            }
+            ParseSgfError::IllegalMove { number, error } => {
+                write!(f, "move {} is illegal: {}", number, error)
+            }
+            ParseSgfError::ResultMismatch { claimed, actual } => {
+                write!(f, "the result says {} wins, the moves {}", claimed, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseSgfError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn played(mv: Move, comment: Option<&str>) -> RecordedMove {
+        RecordedMove {
+            mv,
+            comment: comment.map(str::to_string),
+        }
+    }
+
+    fn record() -> GameRecord {
+        GameRecord {
+            size: 3,
+            white: Some("AlphaBeta_4".to_string()),
+            black: Some("MCTS [v2]".to_string()),
+            moves: vec![
+                played(Move::Cell(0, 1), Some("1.2s")),
+                played(Move::Swap, None),
+                played(Move::Cell(1, 1), Some("score: +3")),
+                played(Move::Resign, None),
+            ],
+            winner: Some(Color::White),
+        }
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(
+            record().to_sgf(),
+            format!(
+                "(;FF[4]GM[11]AP[rust_hex:{}]SZ[3]PB[AlphaBeta_4]PW[MCTS [v2\\]]RE[B+Resign]\n\
+                 ;B[a2]C[1.2s]\n\
+                 ;W[swap-pieces]\n\
+                 ;B[b2]C[score: +3]\n\
+                 ;W[resign]\n\
+                 )\n",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let record = record();
+        assert_eq!(GameRecord::from_sgf(&record.to_sgf()), Ok(record.clone()));
+
+        let board = record.board().unwrap();
+        assert_eq!(board.get(1, 0), Color::Black);
+        assert_eq!(board.get(1, 1), Color::White);
+        assert_eq!(board.moves().len(), 3);
+    }
+
+    #[test]
+    fn read_hexgui() {
+        // Black (White here) wins along the file's b column, which is the
+        // second row here. The variation and unknown properties are ignored.
+        let sgf = "(;AP[HexGUI:0.9.GIT]FF[4]GM[11]SZ[3]GC[a game]
+            ;B[b1]C[first \\] comment\\\n continued];W[a2];B[b2]
+            (;W[c1];B[b3])
+            (;W[a3]))";
+        let record = GameRecord::from_sgf(sgf).unwrap();
+        assert_eq!(record.white, None);
+        assert_eq!(
+            record
+                .moves
+                .iter()
+                .map(|played| played.mv)
+                .collect::<Vec<_>>(),
+            [
+                Move::Cell(1, 0),
+                Move::Cell(0, 1),
+                Move::Cell(1, 1),
+                Move::Cell(2, 0),
+                Move::Cell(1, 2)
+            ]
+        );
+        assert_eq!(
+            record.moves[0].comment.as_deref(),
+            Some("first ] comment continued")
+        );
+        assert_eq!(record.winner, Some(Color::White));
+        assert_eq!(record.board().unwrap().winner(), Some(Color::White));
+    }
+
+    #[test]
+    fn little_golem() {
+        let record = record();
+        assert_eq!(
+            record.to_little_golem(),
+            "(;FF[4]EV[hex]SZ[3]PW[AlphaBeta_4]PB[MCTS [v2\\]]RE[W]\n\
+             ;W[ba]C[1.2s]\n\
+             ;B[swap]\n\
+             ;W[bb]C[score: +3]\n\
+             ;B[resign]\n\
+             )\n"
+        );
+        assert_eq!(
+            GameRecord::from_little_golem(&record.to_little_golem()),
+            Ok(record.clone())
+        );
+
+        let downloaded = "(;FF[4]EV[hex.mc.2024.jan.1.3]PB[bob]PW[alice]SZ[3]RE[W]\
+            GC[ game #1]SO[http://www.littlegolem.com];W[ba];B[swap];W[bb];B[resign])";
+        let read = GameRecord::from_little_golem(downloaded).unwrap();
+        assert_eq!(read.white.as_deref(), Some("alice"));
+        assert_eq!(
+            read.moves
+                .iter()
+                .map(|played| played.mv)
+                .collect::<Vec<_>>(),
+            record
+                .moves
+                .iter()
+                .map(|played| played.mv)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(read.winner, Some(Color::White));
+
+        // Lost on time: the result stands without a winner on the board.
+        let timeout = GameRecord::from_little_golem("(;SZ[3]RE[B];W[ba])").unwrap();
+        assert_eq!(timeout.winner, Some(Color::Black));
+        assert_eq!(
+            GameRecord::from_little_golem("(;SZ[3];W[b1])"),
+            Err(ParseSgfError::InvalidMove {
+                number: 1,
+                value: "b1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        use ParseSgfError::*;
+
+        let cases = [
+            (
+                "",
+                Syntax {
+                    offset: 0,
+                    expected: "'('",
+                },
+            ),
+            (
+                "(;SZ[3];B[a1]",
+                Syntax {
+                    offset: 13,
+                    expected: "')'",
+                },
+            ),
+            (
+                "(;SZ[3",
+                Syntax {
+                    offset: 6,
+                    expected: "']'",
+                },
+            ),
+            (
+                "(;SZ)",
+                Syntax {
+                    offset: 4,
+                    expected: "'[' after a property name",
+                },
+            ),
+            ("(;GM[1]SZ[19])", UnsupportedGame("1".to_string())),
+            ("(;GM[11])", MissingSize),
+            ("(;SZ[3:4])", InvalidSize("3:4".to_string())),
+            ("(;SZ[20])", InvalidSize("20".to_string())),
+            ("(;SZ[3]AB[a1])", UnsupportedProperty("AB".to_string())),
+            (
+                "(;SZ[3];B[a1];B[a2])",
+                OutOfTurn {
+                    number: 2,
+                    found: "B".to_string(),
+                },
+            ),
+            (
+                "(;SZ[3];B[pass])",
+                InvalidMove {
+                    number: 1,
+                    value: "pass".to_string(),
+                },
+            ),
+            (
+                "(;SZ[3];B[a1];W[a1])",
+                IllegalMove {
+                    number: 2,
+                    error: HexError::Occupied { x: 0, y: 0 },
+                },
+            ),
+            (
+                "(;SZ[3];B[a1];W[b1];B[swap-pieces])",
+                IllegalMove {
+                    number: 3,
+                    error: HexError::SwapNotAllowed,
+                },
+            ),
+            (
+                "(;SZ[3];B[a1];W[resign];B[b1])",
+                IllegalMove {
+                    number: 3,
+                    error: HexError::GameFinished,
+                },
+            ),
+            (
+                "(;SZ[3]RE[W+];B[a1];W[resign])",
+                ResultMismatch {
+                    claimed: Color::Black,
+                    actual: Color::White,
+                },
+            ),
+        ];
+        for (input, error) in cases {
+            assert_eq!(GameRecord::from_sgf(input), Err(error), "{:?}", input);
+        }
+    }
+}
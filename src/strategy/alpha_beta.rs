@@ -4,11 +4,12 @@ use crate::{
     board::Board,
     color::Color,
     evaluation::Evaluation,
+    hex_move::Move,
     log::{LogFlag, LogLevel},
     score::Score,
 };
 
-use super::Strategy;
+use super::{Strategy, swap_by_evaluation};
 
 #[derive(Clone)]
 pub struct AlphaBeta {
@@ -18,10 +19,10 @@ pub struct AlphaBeta {
 }
 
 impl Strategy for AlphaBeta {
-    fn next_move(&self, board: &Board, duration: Option<Duration>) -> (usize, usize) {
+    fn next_move(&self, board: &Board, duration: Option<Duration>) -> Move {
         // update duration if it's not None
         match duration {
-            None => self.alpha_beta(board, self.max_depth, duration),
+            None => self.alpha_beta(board, self.max_depth, duration).into(),
             Some(duration_unwrapped) => {
                 let time = std::time::Instant::now();
                 let mut depth = 1;
@@ -33,10 +34,14 @@ impl Strategy for AlphaBeta {
                 if self.log_level.is(LogFlag::SearchDepth) {
                     println!("Depth: {} in {:?}", depth, time.elapsed());
                 }
-                best_move
+                best_move.into()
             }
         }
     }
+
+    fn swap(&self, board: &Board, _duration: Option<Duration>) -> bool {
+        swap_by_evaluation(self.evaluation.as_ref(), board)
+    }
 }
 
 impl AlphaBeta {
@@ -64,8 +69,10 @@ impl AlphaBeta {
         depth: usize,
         duration: Option<Duration>,
     ) -> (usize, usize) {
+        // One copy per search, on which moves are played and undone.
+        let mut board = board.clone();
         match self._alpha_beta(
-            board,
+            &mut board,
             depth,
             Score::BlackCheckMate,
             Score::WhiteCheckMate,
@@ -83,7 +90,7 @@ impl AlphaBeta {
 
     fn _alpha_beta(
         &self,
-        board: &Board,
+        board: &mut Board,
         depth: usize,
         alpha: Score,
         beta: Score,
@@ -102,23 +109,21 @@ impl AlphaBeta {
             return (self.evaluation.score(board), None);
         }
 
-        if let Some(duration) = duration {
-            if duration.as_millis() < 100 {
-                return (self.evaluation.score(board), None);
-            }
+        if let Some(duration) = duration
+            && duration.as_millis() < 100
+        {
+            return (self.evaluation.score(board), None);
         }
 
         let mut value: Score;
         let mut best_move = board.first_possible_move().unwrap();
-        let possible_moves = board.possible_moves();
-
         if board.next_color() == Color::White {
             value = Score::BlackCheckMate;
-            for (x, y) in possible_moves {
-                let mut new_board = board.clone();
-                new_board.play(x, y);
+            for (x, y) in board.empty_cells() {
+                board.play(x, y);
 
-                let (score, _) = self._alpha_beta(&new_board, depth - 1, alpha, beta, duration);
+                let (score, _) = self._alpha_beta(board, depth - 1, alpha, beta, duration);
+                board.undo();
 
                 best_moves.push((score, (x, y)));
 
@@ -141,10 +146,10 @@ impl AlphaBeta {
             }
         } else {
             value = Score::WhiteCheckMate;
-            for (x, y) in possible_moves {
-                let mut new_board = board.clone();
-                new_board.play(x, y);
-                let (score, _) = self._alpha_beta(&new_board, depth - 1, alpha, beta, duration);
+            for (x, y) in board.empty_cells() {
+                board.play(x, y);
+                let (score, _) = self._alpha_beta(board, depth - 1, alpha, beta, duration);
+                board.undo();
 
                 best_moves.push((score, (x, y)));
 
@@ -177,13 +182,13 @@ impl AlphaBeta {
             }
         };
 
-        if depth == self.max_depth {
+        if depth == self.max_depth && self.log_level.is(LogFlag::Score) {
             println!("{}", board);
             best_moves.sort_by(move_cmp);
             println!("{:?}", best_moves);
-            println!("Board score : {}", self.evaluation.score(board)); // TODO: remove this debug print
-            println!("Deep score : {}", value); // TODO: remove this debug print
-            println!("Best move : {:?}", (best_move.0 + 1, best_move.1 + 1)); // TODO: remove this debug print
+            println!("Board score : {}", self.evaluation.score(board));
+            println!("Deep score : {}", value);
+            println!("Best move : {}", Move::from(best_move));
         }
 
         (value.next_back().unwrap(), Some(best_move))
@@ -200,11 +205,11 @@ mod tests {
     #[test]
     fn alpha_beta() {
         let player = AlphaBeta::new(Rc::new(Evaluation1::new()), 5, Rc::default());
-        let mut board = Board::new();
+        let mut board = Board::default();
         board.play(0, 0);
         board.play(1, 0);
         board.play(1, 1);
-        board.play(1, 1);
+        board.play(2, 1);
 
         println!("{}", board);
         let best_move = player.next_move(&board, None);
@@ -4,11 +4,12 @@ use crate::{
     board::Board,
     color::Color,
     evaluation::Evaluation,
+    hex_move::Move,
     log::{LogFlag, LogLevel},
     score::Score,
 };
 
-use super::Strategy;
+use super::{Strategy, swap_by_evaluation};
 
 #[derive(Clone)]
 pub struct AlphaBeta2 {
@@ -18,10 +19,10 @@ pub struct AlphaBeta2 {
 }
 
 impl Strategy for AlphaBeta2 {
-    fn next_move(&self, board: &Board, duration: Option<Duration>) -> (usize, usize) {
+    fn next_move(&self, board: &Board, duration: Option<Duration>) -> Move {
         // update duration if it's not None
         match duration {
-            None => self.alpha_beta(board, self.max_depth, duration),
+            None => self.alpha_beta(board, self.max_depth, duration).into(),
             Some(duration_unwrapped) => {
                 let time = std::time::Instant::now();
                 let mut depth = 1;
@@ -33,10 +34,14 @@ impl Strategy for AlphaBeta2 {
                 if self.log_level.is(LogFlag::SearchDepth) {
                     println!("Depth: {} in {:?}", depth, time.elapsed());
                 }
-                best_move
+                best_move.into()
             }
         }
     }
+
+    fn swap(&self, board: &Board, _duration: Option<Duration>) -> bool {
+        swap_by_evaluation(self.evaluation.as_ref(), board)
+    }
 }
 
 impl AlphaBeta2 {
@@ -58,18 +63,13 @@ impl AlphaBeta2 {
         self.max_depth = max_depth;
     }
 
-    fn possible_moves_sorted(&self, board: &Board) -> Vec<(usize, usize)> {
-        let mut s_moves = board
-            .possible_moves()
-            .iter()
-            .map(|(x, y)| {
-                let mut board = board.clone();
-                board.play(*x, *y);
-                let score = self.evaluation.score(&board);
-                //println!("{} {} {}", x+1, y+1, score);
-                ((*x, *y), score)
-            })
-            .collect::<Vec<((usize, usize), Score)>>();
+    fn possible_moves_sorted(&self, board: &mut Board) -> Vec<(usize, usize)> {
+        let mut s_moves = Vec::new();
+        for (x, y) in board.empty_cells() {
+            board.play(x, y);
+            s_moves.push(((x, y), self.evaluation.score(board)));
+            board.undo();
+        }
 
         s_moves.sort_by(|(_, score_a), (_, score_b)| score_a.partial_cmp(score_b).unwrap());
 
@@ -101,8 +101,10 @@ impl AlphaBeta2 {
         depth: usize,
         duration: Option<Duration>,
     ) -> (usize, usize) {
+        // One copy per search, on which moves are played and undone.
+        let mut board = board.clone();
         match self._alpha_beta(
-            board,
+            &mut board,
             depth,
             Score::BlackCheckMate,
             Score::WhiteCheckMate,
@@ -120,7 +122,7 @@ impl AlphaBeta2 {
 
     fn _alpha_beta(
         &self,
-        board: &Board,
+        board: &mut Board,
         depth: usize,
         alpha: Score,
         beta: Score,
@@ -137,10 +139,10 @@ impl AlphaBeta2 {
             return (self.evaluation.score(board), None);
         }
 
-        if let Some(duration) = duration {
-            if duration.as_millis() < 100 {
-                return (self.evaluation.score(board), None);
-            }
+        if let Some(duration) = duration
+            && duration.as_millis() < 100
+        {
+            return (self.evaluation.score(board), None);
         }
 
         let mut value: Score;
@@ -152,10 +154,10 @@ impl AlphaBeta2 {
         if board.next_color() == Color::White {
             value = Score::BlackCheckMate;
             for (x, y) in possible_moves {
-                let mut new_board: Board<7> = board.clone();
-                new_board.play(x, y);
+                board.play(x, y);
 
-                let (score, _) = self._alpha_beta(&new_board, depth - 1, alpha, beta, duration);
+                let (score, _) = self._alpha_beta(board, depth - 1, alpha, beta, duration);
+                board.undo();
 
                 if score > value {
                     value = score;
@@ -177,9 +179,9 @@ impl AlphaBeta2 {
         } else {
             value = Score::WhiteCheckMate;
             for (x, y) in possible_moves {
-                let mut new_board = board.clone();
-                new_board.play(x, y);
-                let (score, _) = self._alpha_beta(&new_board, depth - 1, alpha, beta, duration);
+                board.play(x, y);
+                let (score, _) = self._alpha_beta(board, depth - 1, alpha, beta, duration);
+                board.undo();
                 if score < value {
                     value = score;
                     best_move = (x, y);
@@ -202,7 +204,7 @@ impl AlphaBeta2 {
         if depth == self.max_depth {
             //println!("Board score : {}", self.evaluation.score(board)); // TODO: remove this debug print
             //println!("Deep score : {}", value); // TODO: remove this debug print
-            //println!("Best move : {:?}", (best_move.0 + 1, best_move.1 + 1)); // TODO: remove this debug print
+            //println!("Best move : {}", Move::from(best_move)); // TODO: remove this debug print
         }
 
         (value.next_back().unwrap(), Some(best_move))
@@ -219,11 +221,11 @@ mod tests {
     #[test]
     fn alpha_beta_2() {
         let player = AlphaBeta2::new(Rc::new(Evaluation1::new()), 5, Rc::default());
-        let mut board = Board::new();
+        let mut board = Board::default();
         board.play(0, 0);
         board.play(1, 0);
         board.play(1, 1);
-        board.play(1, 1);
+        board.play(2, 1);
 
         println!("{}", board);
         let best_move = player.next_move(&board, None);
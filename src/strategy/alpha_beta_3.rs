@@ -4,11 +4,13 @@ use crate::{
     board::Board,
     color::Color,
     evaluation::Evaluation,
+    hex_move::Move,
     log::{LogFlag, LogLevel},
     score::Score,
+    symmetry::Symmetry,
 };
 
-use super::Strategy;
+use super::{Strategy, swap_by_evaluation};
 
 #[derive(Clone)]
 pub struct AlphaBeta3 {
@@ -18,10 +20,10 @@ pub struct AlphaBeta3 {
 }
 
 impl Strategy for AlphaBeta3 {
-    fn next_move(&self, board: &Board, duration: Option<Duration>) -> (usize, usize) {
+    fn next_move(&self, board: &Board, duration: Option<Duration>) -> Move {
         // update duration if it's not None
         match duration {
-            None => self.alpha_beta(board, self.max_depth, duration),
+            None => self.alpha_beta(board, self.max_depth, duration).into(),
             Some(duration_unwrapped) => {
                 let time = std::time::Instant::now();
                 let mut depth = 1;
@@ -33,10 +35,14 @@ impl Strategy for AlphaBeta3 {
                 if self.log_level.is(LogFlag::SearchDepth) {
                     println!("Depth: {} in {:?}", depth, time.elapsed());
                 }
-                best_move
+                best_move.into()
             }
         }
     }
+
+    fn swap(&self, board: &Board, _duration: Option<Duration>) -> bool {
+        swap_by_evaluation(self.evaluation.as_ref(), board)
+    }
 }
 
 impl AlphaBeta3 {
@@ -66,7 +72,16 @@ impl AlphaBeta3 {
     ) -> (usize, usize) {
         #[allow(unused_mut)]
         let mut score_dict = HashMap::new();
-        match self._alpha_beta(board, depth, f64::MIN, f64::MAX, duration, &mut score_dict) {
+        // One copy per search, on which moves are played and undone.
+        let mut board = board.clone();
+        match self._alpha_beta(
+            &mut board,
+            depth,
+            f64::MIN,
+            f64::MAX,
+            duration,
+            &mut score_dict,
+        ) {
             (score, Some((x, y))) => {
                 if self.log_level.is(LogFlag::Score) {
                     println!("Score: {} with depth {}", score, depth);
@@ -77,30 +92,24 @@ impl AlphaBeta3 {
         }
     }
 
-    fn get_score(&self, board: &Board, score_dict: &mut HashMap<Board, Score>) -> Score {
-        if score_dict.contains_key(board) {
-            score_dict[board]
-        } else {
-            self.evaluation.score(board)
+    fn get_score(&self, board: &Board, score_dict: &mut HashMap<u64, Score>) -> Score {
+        match lookup(score_dict, board) {
+            Some(score) => score,
+            None => self.evaluation.score(board),
         }
     }
 
     fn possible_moves_sorted(
         &self,
-        board: &Board,
-        score_dict: &mut HashMap<Board, Score>,
+        board: &mut Board,
+        score_dict: &mut HashMap<u64, Score>,
     ) -> Vec<(usize, usize)> {
-        let mut s_moves = board
-            .possible_moves()
-            .iter()
-            .map(|(x, y)| {
-                let mut board = board.clone();
-                board.play(*x, *y);
-                let score = self.get_score(&board, score_dict);
-                //println!("{} {} {}", x+1, y+1, score);
-                ((*x, *y), score)
-            })
-            .collect::<Vec<((usize, usize), Score)>>();
+        let mut s_moves = Vec::new();
+        for (x, y) in board.empty_cells() {
+            board.play(x, y);
+            s_moves.push(((x, y), self.get_score(board, score_dict)));
+            board.undo();
+        }
 
         s_moves.sort_by(|(_, score_a), (_, score_b)| score_a.partial_cmp(score_b).unwrap());
 
@@ -129,12 +138,12 @@ impl AlphaBeta3 {
     #[allow(clippy::too_many_arguments)]
     fn _alpha_beta(
         &self,
-        board: &Board,
+        board: &mut Board,
         depth: usize,
         alpha: f64,
         beta: f64,
         duration: Option<Duration>,
-        score_dict: &mut HashMap<Board, Score>,
+        score_dict: &mut HashMap<u64, Score>,
     ) -> (Score, Option<(usize, usize)>) {
         let mut alpha = alpha;
         let mut beta = beta;
@@ -147,10 +156,10 @@ impl AlphaBeta3 {
             return (self.get_score(board, score_dict), None);
         }
 
-        if let Some(duration) = duration {
-            if duration.as_millis() < 100 {
-                return (self.evaluation.score(board), None);
-            }
+        if let Some(duration) = duration
+            && duration.as_millis() < 100
+        {
+            return (self.evaluation.score(board), None);
         }
 
         let mut value: f64;
@@ -162,22 +171,22 @@ impl AlphaBeta3 {
         if board.next_color() == Color::White {
             value = f64::MIN;
             for (x, y) in possible_moves {
-                let mut new_board = board.clone();
-                new_board.play(x, y);
+                board.play(x, y);
 
-                let score = match score_dict.get(&new_board) {
-                    Some(score) => *score,
+                let score = match lookup(score_dict, board) {
+                    Some(score) => score,
                     None => {
-                        self._alpha_beta(&new_board, depth - 1, alpha, beta, duration, score_dict)
+                        self._alpha_beta(board, depth - 1, alpha, beta, duration, score_dict)
                             .0
                     }
                 };
 
                 if f64::from(score) > value {
                     value = score.into();
-                    score_dict.insert(new_board.clone(), score);
+                    store(score_dict, board, score);
                     best_move = (x, y);
                 }
+                board.undo();
 
                 if value > alpha {
                     alpha = value;
@@ -187,23 +196,23 @@ impl AlphaBeta3 {
                     break;
                 }
 
-                if depth == self.max_depth {
-                    println!("{} {} {} {}", x + 1, y + 1, score, depth); // TODO: remove this debug print
+                if depth == self.max_depth && self.log_level.is(LogFlag::Score) {
+                    println!("{} {} {}", Move::Cell(x, y), score, depth);
                 }
             }
         } else {
             value = f64::MAX;
             for (x, y) in possible_moves {
-                let mut new_board = board.clone();
-                new_board.play(x, y);
+                board.play(x, y);
 
-                let score = match score_dict.get(&new_board) {
-                    Some(score) => *score,
+                let score = match lookup(score_dict, board) {
+                    Some(score) => score,
                     None => {
-                        self._alpha_beta(&new_board, depth - 1, alpha, beta, duration, score_dict)
+                        self._alpha_beta(board, depth - 1, alpha, beta, duration, score_dict)
                             .0
                     }
                 };
+                board.undo();
 
                 if f64::from(score) < value {
                     value = score.into();
@@ -218,16 +227,16 @@ impl AlphaBeta3 {
                     break;
                 }
 
-                if depth == self.max_depth {
-                    println!("{} {} {} {}", x + 1, y + 1, score, depth); // TODO: remove this debug print
+                if depth == self.max_depth && self.log_level.is(LogFlag::Score) {
+                    println!("{} {} {}", Move::Cell(x, y), score, depth);
                 }
             }
         }
 
-        if depth == self.max_depth {
-            println!("Board score : {}", self.evaluation.score(board)); // TODO: remove this debug print
-            println!("Deep score : {}", value); // TODO: remove this debug print
-            println!("Best move : {:?}", (best_move.0 + 1, best_move.1 + 1)); // TODO: remove this debug print
+        if depth == self.max_depth && self.log_level.is(LogFlag::Score) {
+            println!("Board score : {}", self.evaluation.score(board));
+            println!("Deep score : {}", value);
+            println!("Best move : {}", Move::from(best_move));
         }
 
         (
@@ -237,6 +246,25 @@ impl AlphaBeta3 {
     }
 }
 
+/// Scores are shared between symmetric positions, negated when the
+/// symmetry swaps the colors.
+fn lookup(score_dict: &HashMap<u64, Score>, board: &Board) -> Option<Score> {
+    let (key, symmetry) = board.canonical_key();
+    score_dict.get(&key).map(|&score| oriented(score, symmetry))
+}
+
+fn store(score_dict: &mut HashMap<u64, Score>, board: &Board, score: Score) {
+    let (key, symmetry) = board.canonical_key();
+    score_dict.insert(key, oriented(score, symmetry));
+}
+
+fn oriented(score: Score, symmetry: Symmetry) -> Score {
+    match symmetry.swaps_colors() {
+        true => -score,
+        false => score,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::evaluation::Evaluation1;
@@ -247,11 +275,11 @@ mod tests {
     #[test]
     fn alpha_beta_3() {
         let player = AlphaBeta3::new(Rc::new(Evaluation1::new()), 5, Rc::default());
-        let mut board = Board::new();
+        let mut board = Board::default();
         board.play(0, 0);
         board.play(1, 0);
         board.play(1, 1);
-        board.play(1, 1);
+        board.play(2, 1);
 
         println!("{}", board);
         let best_move = player.next_move(&board, None);
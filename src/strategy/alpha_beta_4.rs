@@ -1,27 +1,38 @@
-use std::{rc::Rc, time::Duration};
+use std::{cell::Cell, rc::Rc, time::Duration};
 
 use crate::{
     board::Board,
     color::Color,
     evaluation::Evaluation,
+    hex_move::Move,
+    inferior,
     log::{LogFlag, LogLevel},
     score::Score,
 };
 
-use super::Strategy;
+use super::{Strategy, swap_by_evaluation};
 
 #[derive(Clone)]
 pub struct AlphaBeta4 {
     max_depth: usize,
     evaluation: Rc<dyn Evaluation>,
     log_level: Rc<LogLevel>,
+    /// Leave out dead, captured and dominated cells from the moves searched.
+    inferior_pruning: bool,
+    /// Fill captured cells before evaluating a position.
+    fill_captured: bool,
+    /// Only search the mustplay region when the opponent threatens to win.
+    mustplay: bool,
+    /// Positions visited since the last call to `next_move`.
+    nodes: Cell<u64>,
 }
 
 impl Strategy for AlphaBeta4 {
-    fn next_move(&self, board: &Board, duration: Option<Duration>) -> (usize, usize) {
+    fn next_move(&self, board: &Board, duration: Option<Duration>) -> Move {
+        self.nodes.set(0);
         // update duration if it's not None
         match duration {
-            None => self.alpha_beta(board, self.max_depth, duration),
+            None => self.alpha_beta(board, self.max_depth, duration).into(),
             Some(duration_unwrapped) => {
                 let time = std::time::Instant::now();
                 let mut depth = 1;
@@ -33,10 +44,14 @@ impl Strategy for AlphaBeta4 {
                 if self.log_level.is(LogFlag::SearchDepth) {
                     println!("Depth: {} in {:?}", depth, time.elapsed());
                 }
-                best_move
+                best_move.into()
             }
         }
     }
+
+    fn swap(&self, board: &Board, _duration: Option<Duration>) -> bool {
+        swap_by_evaluation(self.evaluation.as_ref(), board)
+    }
 }
 
 impl AlphaBeta4 {
@@ -50,6 +65,10 @@ impl AlphaBeta4 {
             evaluation,
             max_depth,
             log_level,
+            inferior_pruning: false,
+            fill_captured: false,
+            mustplay: false,
+            nodes: Cell::new(0),
         }
     }
 
@@ -58,18 +77,66 @@ impl AlphaBeta4 {
         self.max_depth = max_depth;
     }
 
-    fn possible_moves_sorted(&self, board: &Board) -> Vec<(usize, usize)> {
-        let mut s_moves = board
-            .possible_moves()
-            .iter()
-            .map(|(x, y)| {
-                let mut board = board.clone();
-                board.play(*x, *y);
-                let score = self.evaluation.score(&board);
-                //println!("{} {} {}", x+1, y+1, score);
-                ((*x, *y), score)
-            })
-            .collect::<Vec<((usize, usize), Score)>>();
+    #[allow(unused)]
+    pub fn set_inferior_pruning(&mut self, inferior_pruning: bool) {
+        self.inferior_pruning = inferior_pruning;
+    }
+
+    #[allow(unused)]
+    pub fn set_fill_captured(&mut self, fill_captured: bool) {
+        self.fill_captured = fill_captured;
+    }
+
+    #[allow(unused)]
+    pub fn set_mustplay(&mut self, mustplay: bool) {
+        self.mustplay = mustplay;
+    }
+
+    /// Number of positions visited by the last `next_move`, over all the
+    /// depths of the iterative deepening.
+    pub fn nodes(&self) -> u64 {
+        self.nodes.get()
+    }
+
+    fn score(&self, board: &Board) -> Score {
+        if self.fill_captured {
+            let filled = inferior::fill_captured(board, board.next_color());
+            if let Some(winner) = filled.winner() {
+                return winner.win_score();
+            }
+            self.evaluation.score(&filled)
+        } else {
+            self.evaluation.score(board)
+        }
+    }
+
+    fn possible_moves_sorted(&self, board: &mut Board) -> Vec<(usize, usize)> {
+        let mut possible_moves = if self.inferior_pruning {
+            inferior::pruned_moves(board)
+        } else {
+            board.possible_moves()
+        };
+        if self.mustplay
+            && let Some(mustplay) = board.mustplay()
+            && !mustplay.is_empty()
+        {
+            let restricted: Vec<(usize, usize)> = possible_moves
+                .iter()
+                .filter(|cell| mustplay.contains(cell))
+                .copied()
+                .collect();
+            possible_moves = if restricted.is_empty() {
+                mustplay
+            } else {
+                restricted
+            };
+        }
+        let mut s_moves = Vec::with_capacity(possible_moves.len());
+        for (x, y) in possible_moves {
+            board.play(x, y);
+            s_moves.push(((x, y), self.evaluation.score(board)));
+            board.undo();
+        }
 
         s_moves.sort_by(|(_, score_a), (_, score_b)| score_a.partial_cmp(score_b).unwrap());
 
@@ -101,8 +168,10 @@ impl AlphaBeta4 {
         depth: usize,
         duration: Option<Duration>,
     ) -> (usize, usize) {
+        // One copy per search, on which moves are played and undone.
+        let mut board = board.clone();
         match self._alpha_beta(
-            board,
+            &mut board,
             depth,
             Score::BlackCheckMate,
             Score::WhiteCheckMate,
@@ -120,12 +189,13 @@ impl AlphaBeta4 {
 
     fn _alpha_beta(
         &self,
-        board: &Board,
+        board: &mut Board,
         depth: usize,
         alpha: Score,
         beta: Score,
         duration: Option<Duration>,
     ) -> (Score, Option<(usize, usize)>) {
+        self.nodes.set(self.nodes.get() + 1);
         let mut alpha = alpha;
         let mut beta = beta;
 
@@ -134,28 +204,28 @@ impl AlphaBeta4 {
         }
 
         if depth == 0 {
-            return (self.evaluation.score(board), None);
+            return (self.score(board), None);
         }
 
-        if let Some(duration) = duration {
-            if duration.as_millis() < 100 {
-                return (self.evaluation.score(board), None);
-            }
+        if let Some(duration) = duration
+            && duration.as_millis() < 100
+        {
+            return (self.score(board), None);
         }
 
         let mut value: Score;
-        let mut best_move = board.first_possible_move().unwrap();
         let mut possible_moves = self.possible_moves_sorted(board);
+        let mut best_move = possible_moves[0];
 
         possible_moves = self.keep_bests_moves(board, possible_moves);
 
         if board.next_color() == Color::White {
             value = Score::BlackCheckMate;
             for (x, y) in possible_moves {
-                let mut new_board = board.clone();
-                new_board.play(x, y);
+                board.play(x, y);
 
-                let (score, _) = self._alpha_beta(&new_board, depth - 1, alpha, beta, duration);
+                let (score, _) = self._alpha_beta(board, depth - 1, alpha, beta, duration);
+                board.undo();
 
                 if score > value {
                     value = score;
@@ -177,9 +247,9 @@ impl AlphaBeta4 {
         } else {
             value = Score::WhiteCheckMate;
             for (x, y) in possible_moves {
-                let mut new_board = board.clone();
-                new_board.play(x, y);
-                let (score, _) = self._alpha_beta(&new_board, depth - 1, alpha, beta, duration);
+                board.play(x, y);
+                let (score, _) = self._alpha_beta(board, depth - 1, alpha, beta, duration);
+                board.undo();
                 if score < value {
                     value = score;
                     best_move = (x, y);
@@ -202,7 +272,7 @@ impl AlphaBeta4 {
         if depth == self.max_depth {
             //println!("Board score : {}", self.evaluation.score(board)); // TODO: remove this debug print
             //println!("Deep score : {}", value); // TODO: remove this debug print
-            //println!("Best move : {:?}", (best_move.0 + 1, best_move.1 + 1)); // TODO: remove this debug print
+            //println!("Best move : {}", Move::from(best_move)); // TODO: remove this debug print
         }
 
         (value.next_back().unwrap(), Some(best_move))
@@ -219,14 +289,46 @@ mod tests {
     #[test]
     fn alpha_beta_4() {
         let player = AlphaBeta4::new(Rc::new(Evaluation1::new()), 5, Rc::default());
-        let mut board = Board::new();
+        let mut board = Board::default();
         board.play(0, 0);
         board.play(1, 0);
         board.play(1, 1);
-        board.play(1, 1);
+        board.play(2, 1);
 
         println!("{}", board);
         let best_move = player.next_move(&board, None);
         println!("{:?}", best_move);
     }
+
+    #[test]
+    fn inferior_pruning() {
+        let mut player = AlphaBeta4::new(Rc::new(Evaluation1::new()), 2, Rc::default());
+        player.set_inferior_pruning(true);
+        player.set_fill_captured(true);
+
+        // b1 and c1 are captured by Black, no need to play there.
+        let mut board = Board::new(4);
+        board.place_stone(Color::White, 0, 0);
+        board.place_stone(Color::White, 0, 3);
+        for y in 0..3 {
+            board.place_stone(Color::Black, 1, y);
+        }
+        board.play(3, 3);
+        let best_move = player.next_move(&board, None);
+        assert!(best_move != Move::Cell(0, 1) && best_move != Move::Cell(0, 2));
+    }
+
+    #[test]
+    fn mustplay() {
+        let mut player = AlphaBeta4::new(Rc::new(Evaluation1::new()), 1, Rc::default());
+        player.set_mustplay(true);
+
+        // Black threatens b3, White has to block it.
+        let mut board = Board::new(4);
+        board.place_stone(Color::Black, 1, 1);
+        board.place_stone(Color::Black, 3, 1);
+        let mustplay = board.mustplay().unwrap();
+        let best_move = player.next_move(&board, None);
+        assert!(matches!(best_move, Move::Cell(x, y) if mustplay.contains(&(x, y))));
+    }
 }
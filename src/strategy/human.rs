@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::{board::Board, color::Color};
+use crate::{board::Board, hex_move::Move};
 
 use super::Strategy;
 
@@ -12,8 +12,8 @@ pub struct Human {
 
 impl Strategy for Human {
     #[allow(unused_variables)]
-    fn next_move(&self, board: &Board, duration: Option<Duration>) -> (usize, usize) {
-        Human::ask_coord(board)
+    fn next_move(&self, board: &Board, duration: Option<Duration>) -> Move {
+        Human::ask_move(board)
     }
 }
 
@@ -23,34 +23,54 @@ impl Human {
         Human { name }
     }
 
-    fn read_coord(input: &str) -> Option<(usize, usize)> {
-        let parts: Vec<&str> = input.split_whitespace().collect();
-
-        if parts.len() == 2 {
-            if let (Ok(num1), Ok(num2)) = (parts[0].parse::<usize>(), parts[1].parse::<usize>()) {
-                return Some((num1, num2));
-            }
+    /// Checks a move typed by the user, e.g. `c3`, `swap` or `resign`.
+    fn read_move(board: &Board, input: &str) -> Result<Move, String> {
+        let mv = input.parse::<Move>().map_err(|error| error.to_string())?;
+        match mv {
+            Move::Cell(x, y) if !board.is_valid(x, y) => Err(format!("{} is not playable", mv)),
+            Move::Swap if !board.can_swap() => Err("swap is not allowed now".to_string()),
+            _ => Ok(mv),
         }
-
-        None
     }
 
-    fn ask_coord(board: &Board) -> (usize, usize) {
+    fn ask_move(board: &Board) -> Move {
         let mut input = String::new();
-        println!("{}'s turn", board.next_color());
+        if board.can_swap() {
+            println!("{}'s turn (e.g. c3, swap or resign)", board.next_color());
+        } else {
+            println!("{}'s turn (e.g. c3 or resign)", board.next_color());
+        }
         std::io::stdin()
             .read_line(&mut input)
             .expect("Failed to read line");
-        match Human::read_coord(&input) {
-            Some((x, y)) => {
-                if x > 0 && y > 0 && board.is_valid(x - 1, y - 1) {
-                    (x - 1, y - 1)
-                } else {
-                    println!("Invalid move, try again ('x y')");
-                    Human::ask_coord(board)
-                }
+        match Human::read_move(board, &input) {
+            Ok(mv) => mv,
+            Err(error) => {
+                println!("{}, try again", error);
+                Human::ask_move(board)
             }
-            None => Human::ask_coord(board),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_move() {
+        let mut board = Board::new(3);
+        board.set_swap_rule(true);
+        board.play(0, 2);
+
+        assert_eq!(Human::read_move(&board, "b2\n"), Ok(Move::Cell(1, 1)));
+        assert_eq!(Human::read_move(&board, "swap"), Ok(Move::Swap));
+        assert_eq!(Human::read_move(&board, "resign"), Ok(Move::Resign));
+        assert!(Human::read_move(&board, "c1").is_err());
+        assert!(Human::read_move(&board, "d1").is_err());
+        assert!(Human::read_move(&board, "1 3").is_err());
+
+        board.play(1, 1);
+        assert!(Human::read_move(&board, "swap").is_err());
+    }
+}
@@ -6,16 +6,19 @@ use std::time::{Duration, Instant};
 use crate::board::Board;
 use crate::color::Color;
 use crate::evaluation::Evaluation;
+use crate::hex_move::Move;
 use crate::log::LogLevel;
 use crate::score::Score;
 
-use super::Strategy;
+use super::{Strategy, swap_by_evaluation};
 
 #[derive(Debug, Clone)]
 struct MCTSNode {
     visits: usize,
     wins: f64,
     children: HashMap<(usize, usize), MCTSNode>,
+    /// Moves to consider from this node, computed on the first visit.
+    moves: Option<Vec<(usize, usize)>>,
 }
 
 impl MCTSNode {
@@ -24,6 +27,7 @@ impl MCTSNode {
             visits: 0,
             wins: 0.0,
             children: HashMap::new(),
+            moves: None,
         }
     }
 
@@ -43,13 +47,20 @@ impl MCTSNode {
 pub struct MCTS {
     evaluation: Rc<dyn Evaluation>,
     log_level: Rc<LogLevel>,
+    /// Only consider the mustplay region when the opponent threatens to win.
+    mustplay: bool,
 }
 
 impl Strategy for MCTS {
-    fn next_move(&self, board: &Board, duration: Option<Duration>) -> (usize, usize) {
+    fn next_move(&self, board: &Board, duration: Option<Duration>) -> Move {
         let time_limit = duration.unwrap_or(Duration::from_secs(1)); // Default 1s if not provided
-        let mut board_clone = board.clone();
-        self.mcts_search(&mut board_clone, time_limit)
+        // One copy per search, on which moves are played and undone.
+        let mut board = board.clone();
+        self.mcts_search(&mut board, time_limit).into()
+    }
+
+    fn swap(&self, board: &Board, _duration: Option<Duration>) -> bool {
+        swap_by_evaluation(self.evaluation.as_ref(), board)
     }
 }
 
@@ -59,17 +70,32 @@ impl MCTS {
         MCTS {
             evaluation,
             log_level,
+            mustplay: false,
         }
     }
 
+    #[allow(unused)]
+    pub fn set_mustplay(&mut self, mustplay: bool) {
+        self.mustplay = mustplay;
+    }
+
+    fn possible_moves(&self, board: &Board) -> Vec<(usize, usize)> {
+        if self.mustplay
+            && let Some(mustplay) = board.mustplay()
+            && !mustplay.is_empty()
+        {
+            return mustplay;
+        }
+        board.possible_moves()
+    }
+
     /// Run MCTS with time constraint
     fn mcts_search(&self, board: &mut Board, duration: Duration) -> (usize, usize) {
         let mut root = MCTSNode::new();
         let start_time = Instant::now();
 
         while start_time.elapsed() < duration {
-            let mut temp_board = board.clone();
-            self.simulate_mcts(&mut root, &mut temp_board);
+            self.simulate_mcts(&mut root, board);
         }
 
         // Choose the move with the most visits
@@ -82,10 +108,14 @@ impl MCTS {
 
     fn simulate_mcts(&self, node: &mut MCTSNode, board: &mut Board) -> Score {
         // 🔹 Check for terminal state (no moves left)
-        let possible_moves = board.possible_moves();
-        if possible_moves.is_empty() {
+        let first_move = node
+            .moves
+            .get_or_insert_with(|| self.possible_moves(board))
+            .first()
+            .copied();
+        let Some(first_move) = first_move else {
             return self.evaluation.score(board); // Return final board score
-        }
+        };
 
         let mut best_move = None;
         let mut best_ucb1 = f64::NEG_INFINITY;
@@ -101,7 +131,7 @@ impl MCTS {
 
         // Expansion: Pick an unexplored move
         if best_move.is_none() {
-            best_move = Some(possible_moves[0]); // Safe because we checked empty case above
+            best_move = Some(first_move);
         }
 
         if let Some((x, y)) = best_move {
@@ -113,6 +143,7 @@ impl MCTS {
 
             // 🔹 Recursive Simulation (Alternate Players!)
             let score = -self.simulate_mcts(child_node, board); // 🔄 Switch turns!
+            board.undo();
 
             // Backpropagation
             child_node.visits += 1;
@@ -4,11 +4,12 @@ use crate::{
     board::Board,
     color::Color,
     evaluation::Evaluation,
+    hex_move::Move,
     log::{LogFlag, LogLevel},
     score::Score,
 };
 
-use super::Strategy;
+use super::{Strategy, swap_by_evaluation};
 
 #[derive(Clone)]
 pub struct MiniMax {
@@ -18,10 +19,10 @@ pub struct MiniMax {
 }
 
 impl Strategy for MiniMax {
-    fn next_move(&self, board: &Board, duration: Option<Duration>) -> (usize, usize) {
+    fn next_move(&self, board: &Board, duration: Option<Duration>) -> Move {
         // update duration if it's not None
         match duration {
-            None => self.minimax(board, self.max_depth, duration),
+            None => self.minimax(board, self.max_depth, duration).into(),
             Some(duration_unwrapped) => {
                 let time = std::time::Instant::now();
                 let mut depth = 1;
@@ -33,10 +34,14 @@ impl Strategy for MiniMax {
                 if self.log_level.is(LogFlag::SearchDepth) {
                     println!("Depth: {} in {:?}", depth, time.elapsed());
                 }
-                best_move
+                best_move.into()
             }
         }
     }
+
+    fn swap(&self, board: &Board, _duration: Option<Duration>) -> bool {
+        swap_by_evaluation(self.evaluation.as_ref(), board)
+    }
 }
 
 impl MiniMax {
@@ -59,7 +64,9 @@ impl MiniMax {
     }
 
     fn minimax(&self, board: &Board, depth: usize, duration: Option<Duration>) -> (usize, usize) {
-        match self._minimax(board, depth, duration) {
+        // One copy per search, on which moves are played and undone.
+        let mut board = board.clone();
+        match self._minimax(&mut board, depth, duration) {
             (score, Some((x, y))) => {
                 if self.log_level.is(LogFlag::Score) {
                     println!("Score: {} with depth {}", score, depth);
@@ -72,7 +79,7 @@ impl MiniMax {
 
     fn _minimax(
         &self,
-        board: &Board,
+        board: &mut Board,
         depth: usize,
         duration: Option<Duration>,
     ) -> (Score, Option<(usize, usize)>) {
@@ -84,10 +91,10 @@ impl MiniMax {
             return (self.evaluation.score(board), None);
         }
 
-        if let Some(duration) = duration {
-            if duration.as_millis() < 100 {
-                return (self.evaluation.score(board), None);
-            }
+        if let Some(duration) = duration
+            && duration.as_millis() < 100
+        {
+            return (self.evaluation.score(board), None);
         }
 
         let mut best_score = board.next_color().opponent().win_score();
@@ -95,10 +102,10 @@ impl MiniMax {
 
         let mut best_moves: Vec<(Score, (usize, usize))> = Vec::new();
 
-        for (x, y) in board.possible_moves() {
-            let mut new_board = board.clone();
-            new_board.play(x, y);
-            let (score, _) = self._minimax(&new_board, depth - 1, duration);
+        for (x, y) in board.empty_cells() {
+            board.play(x, y);
+            let (score, _) = self._minimax(board, depth - 1, duration);
+            board.undo();
             if (board.next_color() == Color::White && score > best_score) || score < best_score {
                 best_score = score;
                 best_move = (x, y);
@@ -126,7 +133,7 @@ impl MiniMax {
             println!("{:?}", best_moves);
             println!("Board score : {}", self.evaluation.score(board)); // TODO: remove this debug print
             println!("Deep score : {}", best_score); // TODO: remove this debug print
-            println!("Best move : {:?}", (best_move.0 + 1, best_move.1 + 1)); // TODO: remove this debug print
+            println!("Best move : {}", Move::from(best_move)); // TODO: remove this debug print
         }*/
 
         (best_score.next_back().unwrap(), Some(best_move))
@@ -143,11 +150,11 @@ mod tests {
     #[test]
     fn mini_max() {
         let player = MiniMax::new(Rc::new(Evaluation1::new()), 5, Rc::default());
-        let mut board = Board::new();
+        let mut board = Board::default();
         board.play(0, 0);
         board.play(1, 0);
         board.play(1, 1);
-        board.play(1, 1);
+        board.play(2, 1);
 
         println!("{}", board);
         let best_move = player.next_move(&board, None);
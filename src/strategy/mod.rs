@@ -16,10 +16,34 @@ pub use mcts::MCTS;
 pub use mini_max::MiniMax;
 pub use random::Random;
 
-use crate::{board::Board, color::Color};
+use crate::{board::Board, color::Color, evaluation::Evaluation, hex_move::Move};
 
 use std::time::Duration;
 
 pub trait Strategy {
-    fn next_move(&self, board: &Board, duration: Option<Duration>) -> (usize, usize);
+    /// Usually a cell, but `Move::Swap` (when `board.can_swap()`) and
+    /// `Move::Resign` are also accepted.
+    fn next_move(&self, board: &Board, duration: Option<Duration>) -> Move;
+
+    /// Asked instead of `next_move` when the swap rule lets this player take
+    /// the opening stone.
+    fn swap(&self, _board: &Board, _duration: Option<Duration>) -> bool {
+        false
+    }
+}
+
+/// Swaps when the evaluation prefers the swapped position for the side to
+/// move.
+pub fn swap_by_evaluation(evaluation: &dyn Evaluation, board: &Board) -> bool {
+    let mut swapped = board.clone();
+    if swapped.swap().is_err() {
+        return false;
+    }
+
+    let (current, after_swap) = (evaluation.score(board), evaluation.score(&swapped));
+    match board.next_color() {
+        Color::White => after_swap > current,
+        Color::Black => after_swap < current,
+        Color::None => false,
+    }
 }
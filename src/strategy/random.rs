@@ -1,6 +1,6 @@
 use std::{rc::Rc, time::Duration};
 
-use crate::{board::Board, color::Color, log::LogLevel};
+use crate::{board::Board, color::Color, hex_move::Move, log::LogLevel};
 use rand::Rng;
 
 use super::Strategy;
@@ -9,7 +9,7 @@ use super::Strategy;
 pub struct Random;
 
 impl Strategy for Random {
-    fn next_move(&self, board: &Board, _duration: Option<Duration>) -> (usize, usize) {
+    fn next_move(&self, board: &Board, _duration: Option<Duration>) -> Move {
         let mut rng = rand::rng();
         let mut x = rng.random_range(0..board.size());
         let mut y = rng.random_range(0..board.size());
@@ -17,7 +17,7 @@ impl Strategy for Random {
             x = rng.random_range(0..board.size());
             y = rng.random_range(0..board.size());
         }
-        (x, y)
+        Move::Cell(x, y)
     }
 }
 
@@ -27,3 +27,9 @@ impl Random {
         Random {}
     }
 }
+
+impl Default for Random {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,113 @@
+use crate::color::Color;
+
+/// The symmetries of a Hex position. Rotating the board by 180° keeps both
+/// players' edges, while mirroring across the long diagonal exchanges them,
+/// so it comes with a color swap to give an equivalent position.
+///
+/// Every symmetry is its own inverse, and they commute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate180,
+    TransposeSwap,
+    Rotate180TransposeSwap,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 4] = [
+        Symmetry::Identity,
+        Symmetry::Rotate180,
+        Symmetry::TransposeSwap,
+        Symmetry::Rotate180TransposeSwap,
+    ];
+
+    fn rotates(self) -> bool {
+        matches!(self, Symmetry::Rotate180 | Symmetry::Rotate180TransposeSwap)
+    }
+
+    /// Whether White and Black exchange roles, so that scores seen from
+    /// White's side must be negated.
+    pub fn swaps_colors(self) -> bool {
+        matches!(
+            self,
+            Symmetry::TransposeSwap | Symmetry::Rotate180TransposeSwap
+        )
+    }
+
+    pub fn cell(self, size: usize, x: usize, y: usize) -> (usize, usize) {
+        let (x, y) = if self.swaps_colors() { (y, x) } else { (x, y) };
+        if self.rotates() {
+            (size - 1 - x, size - 1 - y)
+        } else {
+            (x, y)
+        }
+    }
+
+    pub fn color(self, color: Color) -> Color {
+        if self.swaps_colors() {
+            color.opponent()
+        } else {
+            color
+        }
+    }
+
+    /// Composition of two symmetries, `self` applied after `other`.
+    pub fn then(self, other: Symmetry) -> Symmetry {
+        match (
+            self.rotates() != other.rotates(),
+            self.swaps_colors() != other.swaps_colors(),
+        ) {
+            (false, false) => Symmetry::Identity,
+            (true, false) => Symmetry::Rotate180,
+            (false, true) => Symmetry::TransposeSwap,
+            (true, true) => Symmetry::Rotate180TransposeSwap,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_move::Move;
+
+    #[test]
+    fn involutions() {
+        for symmetry in Symmetry::ALL {
+            assert_eq!(symmetry.then(symmetry), Symmetry::Identity);
+            for x in 0..5 {
+                for y in 0..5 {
+                    let (x2, y2) = symmetry.cell(5, x, y);
+                    assert_eq!(symmetry.cell(5, x2, y2), (x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn composition() {
+        for a in Symmetry::ALL {
+            for b in Symmetry::ALL {
+                let (x, y) = b.cell(7, 1, 4);
+                assert_eq!(a.cell(7, x, y), a.then(b).cell(7, 1, 4));
+                assert_eq!(a.then(b), b.then(a));
+            }
+        }
+    }
+
+    #[test]
+    fn moves() {
+        assert_eq!(
+            Move::Cell(0, 1).transform(Symmetry::Rotate180, 5),
+            Move::Cell(4, 3)
+        );
+        assert_eq!(
+            Move::Cell(0, 1).transform(Symmetry::TransposeSwap, 5),
+            Move::Cell(1, 0)
+        );
+        assert_eq!(
+            Move::Cell(0, 1).transform(Symmetry::Rotate180TransposeSwap, 5),
+            Move::Cell(3, 4)
+        );
+        assert_eq!(Move::Swap.transform(Symmetry::Rotate180, 5), Move::Swap);
+    }
+}
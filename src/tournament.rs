@@ -14,6 +14,7 @@ pub struct Tournament {
     games: Vec<Game>,
     results: Vec<Vec<usize>>,
     nb_games: usize,
+    swap_rule: bool,
     log_level: Rc<LogLevel>,
 }
 
@@ -27,7 +28,11 @@ impl Tournament {
         self.players = players;
     }
 
-    pub fn create_games(&mut self, nb_games: usize) {
+    pub fn set_swap_rule(&mut self, swap_rule: bool) {
+        self.swap_rule = swap_rule;
+    }
+
+    pub fn create_games(&mut self, board_size: usize, nb_games: usize) {
         self.nb_games = nb_games;
         for player1 in self.players.iter() {
             for player2 in self.players.iter() {
@@ -37,6 +42,8 @@ impl Tournament {
                     players.insert(Color::Black, player2.clone());
 
                     let mut game = Game::new(players);
+                    game.set_board_size(board_size);
+                    game.set_swap_rule(self.swap_rule);
                     game.set_log_level(self.log_level.clone());
                     self.games.push(game);
                 }
@@ -47,13 +54,29 @@ impl Tournament {
         self.results = vec![vec![0; n]; n];
     }
 
+    pub fn players(&self) -> &[Rc<Player>] {
+        &self.players
+    }
+
+    /// Games won by White, by White's player then Black's.
+    pub fn results(&self) -> &[Vec<usize>] {
+        &self.results
+    }
+
+    /// The games, in the order they are played, to look at or save once
+    /// played.
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
     pub fn play(&mut self) {
         let n = self.players.len();
         for id in 0..self.games.len() {
             let cell_id = id / self.nb_games;
 
-            self.games[id].play();
-            if self.games[id].winner() == Some(Color::White) {
+            if let Err(error) = self.games[id].play() {
+                println!("Game {}/{} aborted: {}", id + 1, self.games.len(), error);
+            } else if self.games[id].winner() == Some(Color::White) {
                 self.results[cell_id / n][cell_id % n] += 1
             }
 
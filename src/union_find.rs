@@ -0,0 +1,86 @@
+use crate::bitboard::MAX_BOARD_SIZE;
+
+/// Cell indices follow the bitboard layout, the four edges come right after.
+const MAX_CELLS: usize = MAX_BOARD_SIZE * (MAX_BOARD_SIZE + 1);
+const NB_NODES: usize = MAX_CELLS + 4;
+
+pub const TOP: usize = MAX_CELLS;
+pub const BOTTOM: usize = MAX_CELLS + 1;
+pub const LEFT: usize = MAX_CELLS + 2;
+pub const RIGHT: usize = MAX_CELLS + 3;
+
+/// Disjoint sets of stones, with union by rank and path halving.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: [u16; NB_NODES],
+    rank: [u8; NB_NODES],
+}
+
+impl UnionFind {
+    pub fn new() -> UnionFind {
+        let mut parent = [0; NB_NODES];
+        for (node, parent) in parent.iter_mut().enumerate() {
+            *parent = node as u16;
+        }
+
+        UnionFind {
+            parent,
+            rank: [0; NB_NODES],
+        }
+    }
+
+    pub fn find(&mut self, mut node: usize) -> usize {
+        while self.parent[node] as usize != node {
+            let grand_parent = self.parent[self.parent[node] as usize];
+            self.parent[node] = grand_parent;
+            node = grand_parent as usize;
+        }
+        node
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+
+        let (child, root) = if self.rank[a] < self.rank[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[child] = root as u16;
+        if self.rank[child] == self.rank[root] {
+            self.rank[root] += 1;
+        }
+    }
+
+    pub fn is_connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+impl Default for UnionFind {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_find() {
+        let mut union_find = UnionFind::new();
+        assert!(!union_find.is_connected(TOP, BOTTOM));
+
+        union_find.union(TOP, 3);
+        union_find.union(4, BOTTOM);
+        assert!(!union_find.is_connected(TOP, BOTTOM));
+
+        union_find.union(3, 4);
+        assert!(union_find.is_connected(TOP, BOTTOM));
+        assert!(!union_find.is_connected(LEFT, RIGHT));
+    }
+}
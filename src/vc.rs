@@ -0,0 +1,418 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    bitboard::{Bitboard, NB_INDICES},
+    board::Board,
+    cell::Cell,
+    color::Color,
+};
+
+/// Connections kept for each pair of targets. H-search is exponential in
+/// the worst case, so the lists are bounded and the first (smallest)
+/// carriers found are kept.
+const MAX_FULL_CONNECTIONS: usize = 4;
+const MAX_SEMI_CONNECTIONS: usize = 8;
+
+/// What a virtual connection links: one of the player's edges, a group of
+/// their stones, identified by its first stone in row-major order, or an
+/// empty cell. Groups touching an edge are part of that edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    Start,
+    End,
+    Group(usize, usize),
+    Cell(usize, usize),
+}
+
+/// Virtual connections of one player, found with Anshelevich's H-search.
+///
+/// A full connection between two targets holds even if the opponent moves
+/// first, as long as the player answers inside its carrier. A semi
+/// connection needs one more move, its key, to become a full one.
+#[derive(Debug, Clone)]
+pub struct VirtualConnections {
+    size: usize,
+    color: Color,
+    targets: Vec<Target>,
+    /// Target of each stone of the player and each empty cell, by index.
+    points: Vec<Option<usize>>,
+    full: BTreeMap<(usize, usize), Vec<Bitboard>>,
+    semi: BTreeMap<(usize, usize), Vec<(Bitboard, usize)>>,
+}
+
+const START: usize = 0;
+const END: usize = 1;
+
+impl VirtualConnections {
+    pub fn new(board: &Board, color: Color) -> VirtualConnections {
+        let size = board.size();
+        let mut connections = VirtualConnections {
+            size,
+            color,
+            targets: vec![Target::Start, Target::End],
+            points: vec![None; NB_INDICES],
+            full: BTreeMap::new(),
+            semi: BTreeMap::new(),
+        };
+
+        let (start_edge, end_edge) = board.edges(color);
+        let stones = board.stones(color);
+        let mut connected = false;
+        for index in stones.iter() {
+            if connections.points[index].is_some() {
+                continue;
+            }
+            let mut seed = Bitboard::empty();
+            seed.set(index);
+            let group = seed.flood_fill(stones, size);
+            let (start, end) = (
+                !(group & start_edge).is_empty(),
+                !(group & end_edge).is_empty(),
+            );
+            connected |= start && end;
+            let point = match (start, end) {
+                (true, _) => START,
+                (false, true) => END,
+                (false, false) => {
+                    let (x, y) = Bitboard::coord(size, index);
+                    connections.add_target(Target::Group(x, y))
+                }
+            };
+            for index in group.iter() {
+                connections.points[index] = Some(point);
+            }
+        }
+
+        let empty = board.stones(Color::None);
+        for index in empty.iter() {
+            let (x, y) = Bitboard::coord(size, index);
+            connections.points[index] = Some(connections.add_target(Target::Cell(x, y)));
+        }
+
+        if connected {
+            connections.add_full(START, END, Bitboard::empty());
+        }
+
+        // Adjacent targets are connected with an empty carrier.
+        for index in empty.iter() {
+            let point = connections.points[index].unwrap();
+            if start_edge.get(index) {
+                connections.add_full(point, START, Bitboard::empty());
+            }
+            if end_edge.get(index) {
+                connections.add_full(point, END, Bitboard::empty());
+            }
+
+            let (x, y) = Bitboard::coord(size, index);
+            for neighbor in Cell::new(x as i32, y as i32).neighbors(size) {
+                let neighbor = Bitboard::index(size, neighbor.x as usize, neighbor.y as usize);
+                if let Some(other) = connections.points[neighbor] {
+                    connections.add_full(point, other, Bitboard::empty());
+                }
+            }
+        }
+
+        connections.search();
+        connections
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// The target a cell belongs to, `None` for the opponent's stones.
+    pub fn target(&self, x: usize, y: usize) -> Option<Target> {
+        if x >= self.size || y >= self.size {
+            return None;
+        }
+        self.points[Bitboard::index(self.size, x, y)].map(|point| self.targets[point])
+    }
+
+    pub fn is_connected(&self, a: Target, b: Target) -> bool {
+        self.carrier(a, b).is_some()
+    }
+
+    /// Cells of the smallest known full connection between `a` and `b`.
+    pub fn carrier(&self, a: Target, b: Target) -> Option<Vec<(usize, usize)>> {
+        let carriers = self.full.get(&self.key(a, b)?)?;
+        let carrier = carriers.iter().min_by_key(|carrier| carrier.count())?;
+        Some(self.cells(*carrier))
+    }
+
+    /// Moves turning a semi connection between `a` and `b` into a full one.
+    pub fn semi_keys(&self, a: Target, b: Target) -> Vec<(usize, usize)> {
+        let Some(semis) = self.key(a, b).and_then(|key| self.semi.get(&key)) else {
+            return Vec::new();
+        };
+        let mut keys: Vec<(usize, usize)> = semis
+            .iter()
+            .map(|(_, key)| Bitboard::coord(self.size, *key))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// The player's edges are virtually connected: the game is won whoever
+    /// moves next.
+    pub fn edges_connected(&self) -> bool {
+        self.full.contains_key(&(START, END))
+    }
+
+    /// A move connecting the edges for good, if there is one.
+    pub fn winning_move(&self) -> Option<(usize, usize)> {
+        self.semi_keys(Target::Start, Target::End).first().copied()
+    }
+
+    /// Carriers of the connections between the player's edges, full ones
+    /// and semi ones with their key: the opponent must play inside each of
+    /// them not to lose.
+    pub(crate) fn edge_carriers(&self) -> Vec<Bitboard> {
+        let full = self.full.get(&(START, END)).into_iter().flatten().copied();
+        let semi =
+            (self.semi.get(&(START, END)).into_iter().flatten()).map(|(carrier, _)| *carrier);
+        full.chain(semi).collect()
+    }
+
+    pub fn is_won(&self, next_color: Color) -> bool {
+        self.edges_connected() || (next_color == self.color && self.winning_move().is_some())
+    }
+
+    fn add_target(&mut self, target: Target) -> usize {
+        self.targets.push(target);
+        self.targets.len() - 1
+    }
+
+    fn key(&self, a: Target, b: Target) -> Option<(usize, usize)> {
+        let (a, b) = (self.point(a)?, self.point(b)?);
+        Some((a.min(b), a.max(b)))
+    }
+
+    fn point(&self, target: Target) -> Option<usize> {
+        match target {
+            Target::Start => Some(START),
+            Target::End => Some(END),
+            Target::Group(x, y) | Target::Cell(x, y) => self
+                .points
+                .get(Bitboard::index(self.size, x, y))
+                .copied()
+                .flatten()
+                .filter(|&point| self.targets[point] == target),
+        }
+    }
+
+    /// The empty cell a point stands for.
+    fn cell(&self, point: usize) -> Option<usize> {
+        match self.targets[point] {
+            Target::Cell(x, y) => Some(Bitboard::index(self.size, x, y)),
+            _ => None,
+        }
+    }
+
+    fn cells(&self, carrier: Bitboard) -> Vec<(usize, usize)> {
+        carrier
+            .iter()
+            .map(|index| Bitboard::coord(self.size, index))
+            .collect()
+    }
+
+    /// Keeps a connection unless a smaller one is known already.
+    fn add_full(&mut self, a: usize, b: usize, carrier: Bitboard) -> bool {
+        if a == b {
+            return false;
+        }
+        let carriers = self.full.entry((a.min(b), a.max(b))).or_default();
+        if carriers.len() >= MAX_FULL_CONNECTIONS
+            || carriers
+                .iter()
+                .any(|known| known.and_not(carrier).is_empty())
+        {
+            return false;
+        }
+        carriers.push(carrier);
+        true
+    }
+
+    fn add_semi(&mut self, a: usize, b: usize, carrier: Bitboard, key: usize) -> bool {
+        let pair = (a.min(b), a.max(b));
+        if let Some(carriers) = self.full.get(&pair)
+            && carriers
+                .iter()
+                .any(|known| known.and_not(carrier).is_empty())
+        {
+            return false;
+        }
+
+        let semis = self.semi.entry(pair).or_default();
+        if semis.len() >= MAX_SEMI_CONNECTIONS
+            || semis
+                .iter()
+                .any(|(known, _)| known.and_not(carrier).is_empty())
+        {
+            return false;
+        }
+        semis.push((carrier, key));
+        true
+    }
+
+    /// Applies the AND and OR rules until nothing new is found.
+    fn search(&mut self) {
+        loop {
+            let mut changed = false;
+
+            let mut around = vec![Vec::new(); self.targets.len()];
+            for (&(a, b), carriers) in &self.full {
+                for &carrier in carriers {
+                    around[a].push((b, carrier));
+                    around[b].push((a, carrier));
+                }
+            }
+
+            // AND rule: x-z and z-y with disjoint carriers avoiding x and y
+            // give x-y, full through a group, semi through an empty cell
+            // which becomes the key. Going through an edge, or linking two
+            // empty cells, would only multiply the connections to keep.
+            for (z, connections) in around.iter().enumerate().skip(END + 1) {
+                for (i, &(x, x_carrier)) in connections.iter().enumerate() {
+                    for &(y, y_carrier) in &connections[i + 1..] {
+                        if x == y
+                            || (self.cell(x).is_some() && self.cell(y).is_some())
+                            || !(x_carrier & y_carrier).is_empty()
+                            || self.cell(x).is_some_and(|cell| y_carrier.get(cell))
+                            || self.cell(y).is_some_and(|cell| x_carrier.get(cell))
+                        {
+                            continue;
+                        }
+
+                        let mut carrier = x_carrier | y_carrier;
+                        changed |= match self.cell(z) {
+                            Some(key) => {
+                                carrier.set(key);
+                                self.add_semi(x, y, carrier, key)
+                            }
+                            None => self.add_full(x, y, carrier),
+                        };
+                    }
+                }
+            }
+
+            // OR rule: semi connections whose carriers have no common cell
+            // make a full one, as the opponent cannot break them all at once.
+            let pairs: Vec<(usize, usize)> = self.semi.keys().copied().collect();
+            for (a, b) in pairs {
+                if let Some(carrier) = self.combine_semis(a, b) {
+                    changed |= self.add_full(a, b, carrier);
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Greedily picks semi connections, smallest first, until their carriers
+    /// have an empty intersection, and returns the union of their carriers.
+    fn combine_semis(&self, a: usize, b: usize) -> Option<Bitboard> {
+        let mut semis = self.semi[&(a, b)].clone();
+        semis.sort_by_key(|(carrier, _)| carrier.count());
+
+        let mut union = Bitboard::empty();
+        let mut intersection: Option<Bitboard> = None;
+        for (carrier, _) in semis {
+            let next = intersection.map_or(carrier, |intersection| intersection & carrier);
+            if intersection == Some(next) {
+                continue;
+            }
+            union |= carrier;
+            if next.is_empty() {
+                return Some(union);
+            }
+            intersection = Some(next);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bridge() {
+        let mut board = Board::new(5);
+        for (x, y) in [(4, 0), (1, 1), (4, 4), (2, 2)] {
+            board.play(x, y);
+        }
+
+        let connections = VirtualConnections::new(&board, Color::Black);
+        let (a, b) = (Target::Group(1, 1), Target::Group(2, 2));
+        assert_eq!(connections.target(1, 1), Some(a));
+        assert_eq!(connections.target(4, 0), None);
+        assert_eq!(connections.carrier(a, b), Some(vec![(1, 2), (2, 1)]));
+
+        board.play(1, 2);
+        let connections = VirtualConnections::new(&board, Color::Black);
+        assert!(!connections.is_connected(a, b));
+        assert_eq!(connections.semi_keys(a, b), vec![(2, 1)]);
+    }
+
+    #[test]
+    fn edge_template() {
+        let mut board = Board::new(5);
+        board.play(4, 4);
+        board.play(1, 2);
+
+        let connections = VirtualConnections::new(&board, Color::Black);
+        let stone = Target::Group(1, 2);
+        assert_eq!(
+            connections.carrier(Target::Start, stone),
+            Some(vec![(0, 2), (0, 3)])
+        );
+        assert!(!connections.is_connected(Target::End, stone));
+    }
+
+    #[test]
+    fn ziggurat() {
+        let mut board = Board::new(5);
+        board.play(4, 4);
+        board.play(2, 2);
+
+        let connections = VirtualConnections::new(&board, Color::Black);
+        let carrier = connections
+            .carrier(Target::Start, Target::Group(2, 2))
+            .unwrap();
+        assert_eq!(carrier.len(), 8);
+    }
+
+    #[test]
+    fn won_early() {
+        let mut board = Board::new(3);
+        board.play(0, 0);
+        let connections = VirtualConnections::new(&board, Color::Black);
+        assert!(!connections.edges_connected());
+        assert!(connections.is_won(Color::Black));
+
+        let (x, y) = connections.winning_move().unwrap();
+        board.play(x, y);
+        assert_eq!(board.winner(), None);
+        assert!(VirtualConnections::new(&board, Color::Black).edges_connected());
+        assert!(!VirtualConnections::new(&board, Color::White).is_won(Color::White));
+    }
+
+    #[test]
+    fn connected() {
+        let mut board = Board::new(2);
+        board.play(0, 0);
+        board.play(0, 1);
+        board.play(1, 1);
+        board.play(1, 0);
+
+        let connections = VirtualConnections::new(&board, Color::Black);
+        assert!(connections.edges_connected());
+        assert_eq!(
+            connections.carrier(Target::Start, Target::End),
+            Some(vec![])
+        );
+    }
+}
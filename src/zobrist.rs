@@ -0,0 +1,88 @@
+use crate::{bitboard::MAX_BOARD_SIZE, color::Color};
+
+/// Fixed seed so keys are identical across runs and can be stored in files.
+const SEED: u64 = 0x4845_585f_5a4f_4252;
+
+const NB_CELLS: usize = MAX_BOARD_SIZE * MAX_BOARD_SIZE;
+
+struct Keys {
+    cells: [[u64; 2]; NB_CELLS],
+    sizes: [u64; MAX_BOARD_SIZE + 1],
+    black_to_move: u64,
+}
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (state, z ^ (z >> 31))
+}
+
+const fn generate() -> Keys {
+    let mut keys = Keys {
+        cells: [[0; 2]; NB_CELLS],
+        sizes: [0; MAX_BOARD_SIZE + 1],
+        black_to_move: 0,
+    };
+    let mut state = SEED;
+    let mut key;
+
+    let mut i = 0;
+    while i < NB_CELLS {
+        (state, key) = splitmix64(state);
+        keys.cells[i][0] = key;
+        (state, key) = splitmix64(state);
+        keys.cells[i][1] = key;
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i <= MAX_BOARD_SIZE {
+        (state, key) = splitmix64(state);
+        keys.sizes[i] = key;
+        i += 1;
+    }
+
+    (_, keys.black_to_move) = splitmix64(state);
+    keys
+}
+
+static KEYS: Keys = generate();
+
+pub fn stone_key(color: Color, x: usize, y: usize) -> u64 {
+    let cell = &KEYS.cells[x * MAX_BOARD_SIZE + y];
+    match color {
+        Color::White => cell[0],
+        Color::Black => cell[1],
+        Color::None => 0,
+    }
+}
+
+pub fn size_key(size: usize) -> u64 {
+    KEYS.sizes[size]
+}
+
+pub fn side_key(color: Color) -> u64 {
+    match color {
+        Color::Black => KEYS.black_to_move,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_distinct() {
+        let mut keys: Vec<u64> = KEYS.cells.iter().flatten().copied().collect();
+        keys.extend(KEYS.sizes);
+        keys.push(KEYS.black_to_move);
+        let nb_keys = keys.len();
+
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), nb_keys);
+    }
+}